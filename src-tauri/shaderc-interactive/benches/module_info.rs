@@ -0,0 +1,76 @@
+//! Times `ModuleInfo::create` on generated compute shaders with many named
+//! ids, like the ones produced by heavy inlining and unrolling.
+//!
+//! Run with `cargo bench --bench module_info`
+
+use rspirv::dr::{Builder, Module};
+use shaderc_interactive::module_info::ModuleInfo;
+use spirv::{AddressingModel, ExecutionModel, FunctionControl, MemoryModel, StorageClass};
+use std::time::{Duration, Instant};
+
+const SIZES: &[u32] = &[1_000, 10_000, 50_000];
+const ITERATIONS: u32 = 5;
+
+/// A single function with `variables` local variables. Every fourth name is
+/// unique, the others are shared like the `i` and `tmp` of inlined code or
+/// look like the suffixed name of a shared one
+fn generate(variables: u32) -> Module {
+    let mut builder = Builder::new();
+    builder.capability(spirv::Capability::Shader);
+    builder.memory_model(AddressingModel::Logical, MemoryModel::GLSL450);
+
+    let void = builder.type_void();
+    let function_type = builder.type_function(void, vec![]);
+    let uint = builder.type_int(32, 0);
+    let pointer = builder.type_pointer(None, StorageClass::Function, uint);
+
+    let main = builder
+        .begin_function(void, None, FunctionControl::NONE, function_type)
+        .unwrap();
+    builder.name(main, "main");
+    builder.begin_block(None).unwrap();
+    let mut shared = 0;
+    for index in 0..variables {
+        let constant = builder.constant_bit32(uint, index);
+        let variable = builder.variable(pointer, None, StorageClass::Function, None);
+        builder.store(variable, constant, None, vec![]).unwrap();
+
+        let name = match index % 4 {
+            0 => format!("value{index}"),
+            1 => "i".to_string(),
+            2 => {
+                shared = variable;
+                "tmp".to_string()
+            },
+            _ => format!("tmp_{shared}"),
+        };
+        builder.name(variable, name);
+    }
+    builder.ret().unwrap();
+    builder.end_function().unwrap();
+
+    builder.entry_point(ExecutionModel::GLCompute, main, "main", vec![]);
+    builder.module()
+}
+
+fn main() {
+    for &size in SIZES {
+        let module = generate(size);
+
+        let mut fastest = Duration::MAX;
+        let mut names = None;
+        for _ in 0..ITERATIONS {
+            let start = Instant::now();
+            let info = ModuleInfo::create(&module).unwrap();
+            fastest = fastest.min(start.elapsed());
+
+            // The names must not change between runs
+            if let Some(names) = &names {
+                assert!(info.names == *names);
+            }
+            names = Some(info.names);
+        }
+
+        println!("{size:>6} variables: {fastest:?}");
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{
+    module_info::{InstructionDisassembly, InstructionDisassemblyLengths, ModuleInfo},
+    operands::InstructionOperands,
+    validation::Validation,
+};
+use eyre::{eyre, Result};
+use rspirv::{
+    binary::Disassemble,
+    dr::{Instruction, Module},
+};
+use serde::{Deserialize, Serialize};
+use spirv::Op;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AnnotatedDisassembly {
+    pub header: Option<String>,
+    /// The file named by `OpSource`. Line annotations referring to any other
+    /// file come from an `#include`
+    pub source_file: Option<String>,
+    /// Source text embedded by `OpSource` and `OpSourceContinued`, keyed by
+    /// file name. Text without a file name is stored under the empty name
+    pub embedded_sources: BTreeMap<String, String>,
+    pub instructions: Vec<AnnotatedInstruction>,
+    /// Validation errors about the module as a whole or about instructions that
+    /// are not shown
+    pub validation_errors: Vec<String>,
+    /// Why the module was not validated, if it was not
+    pub validation_skipped: Option<String>,
+    pub lengths: InstructionDisassemblyLengths,
+    pub info: ModuleInfo,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AnnotatedInstruction {
+    /// Position of the instruction in the module, counting hidden instructions
+    pub index: usize,
+    pub line: Option<LineAnnotation>,
+    pub instruction: String,
+    pub disassembly: InstructionDisassembly,
+    pub validation_errors: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LineAnnotation {
+    pub file: String,
+    pub line: u32,
+}
+
+impl AnnotatedDisassembly {
+    pub fn create(module: &Module, limit_result_name_length: Option<usize>) -> Result<Self> {
+        let info = ModuleInfo::create(module)?;
+
+        let header = module.header.as_ref().map(|h| h.disassemble());
+
+        let mut source_file = None;
+        let mut embedded_sources = BTreeMap::<String, String>::new();
+        let mut embedded_source_name = None;
+        let mut line = None;
+        let mut instructions = Vec::new();
+
+        for (index, instruction) in module.all_inst_iter().enumerate() {
+            let mut add_instruction = true;
+
+            match instruction.class.opcode {
+                Op::Line => {
+                    let file = instruction.id_ref(0)?;
+                    line = Some(LineAnnotation {
+                        file: info
+                            .strings
+                            .get(&file)
+                            .ok_or_else(|| {
+                                eyre!("OpLine refers to {file}, which is not an OpString")
+                            })?
+                            .to_string(),
+                        line: instruction.literal_int32(1)?,
+                    });
+                    add_instruction = false;
+                },
+                Op::NoLine => {
+                    line = None;
+                    add_instruction = false;
+                },
+                Op::Function => {
+                    line = None;
+                },
+                Op::Source => {
+                    let file = instruction
+                        .id_ref(2)
+                        .ok()
+                        .and_then(|file| info.strings.get(&file))
+                        .cloned();
+                    if source_file.is_none() {
+                        source_file = file.clone();
+                    }
+
+                    embedded_source_name = None;
+                    if let Ok(text) = instruction.literal_string(3) {
+                        let name = file.unwrap_or_default();
+                        embedded_sources.insert(name.clone(), text.to_string());
+                        embedded_source_name = Some(name);
+                    }
+                    add_instruction = false;
+                },
+                Op::SourceContinued => {
+                    if let Some(name) = embedded_source_name.as_ref() {
+                        let text = instruction.literal_string(0)?;
+                        embedded_sources
+                            .entry(name.clone())
+                            .or_default()
+                            .push_str(text);
+                    }
+                    add_instruction = false;
+                },
+                Op::Name => {
+                    add_instruction = false;
+                },
+                _ => (),
+            }
+
+            if add_instruction {
+                instructions.push(AnnotatedInstruction {
+                    index,
+                    line: line.clone(),
+                    instruction: instruction.disassemble(),
+                    disassembly: info.disassemble_instruction(instruction),
+                    validation_errors: Vec::new(),
+                });
+            }
+        }
+
+        let lengths = InstructionDisassemblyLengths::for_instructions(
+            instructions.iter().map(|instr| &instr.disassembly),
+            limit_result_name_length,
+        );
+
+        for instr in instructions.iter_mut() {
+            instr.instruction = lengths.format_instruction(&instr.disassembly, false);
+        }
+
+        Ok(Self {
+            header,
+            source_file,
+            embedded_sources,
+            instructions,
+            validation_errors: Vec::new(),
+            validation_skipped: None,
+            lengths,
+            info,
+        })
+    }
+
+    /// The module's instructions in the order they are shown
+    pub fn shown_instructions<'m>(&self, module: &'m Module) -> Vec<&'m Instruction> {
+        let all = module.all_inst_iter().collect::<Vec<_>>();
+        self.instructions
+            .iter()
+            .map(|instruction| all[instruction.index])
+            .collect()
+    }
+
+    /// Attaches validation messages to the instructions they refer to
+    pub fn attach_validation(&mut self, validation: Validation) {
+        let messages = match validation {
+            Validation::Messages(messages) => messages,
+            Validation::Skipped(reason) => {
+                self.validation_skipped = Some(reason);
+                return;
+            },
+        };
+
+        for message in messages {
+            let instruction = message.instruction.and_then(|index| {
+                self.instructions
+                    .iter_mut()
+                    .find(|instruction| instruction.index == index)
+            });
+
+            match instruction {
+                Some(instruction) => instruction.validation_errors.push(message.message),
+                None => self.validation_errors.push(message.message),
+            }
+        }
+    }
+}
@@ -0,0 +1,1548 @@
+//! Assembles SPIR-V text back into a module.
+//!
+//! Both the friendly syntax produced by [`ModuleInfo::disassemble_instruction`]
+//! (`%result OpName %type operands...`) and standard `spirv-as` syntax
+//! (`%result = OpName %type operands...`) are accepted. Ids may be numeric or
+//! arbitrary names, numeric ids keep their number.
+//!
+//! [`ModuleInfo::disassemble_instruction`]: crate::module_info::ModuleInfo::disassemble_instruction
+
+use crate::{
+    diagnostics::{Diagnostic, Diagnostics, Severity},
+    module_info::ModuleInfo,
+};
+use bitflags::Flags;
+use rspirv::{
+    binary::{Consumer, ParseAction},
+    dr::{Instruction, Loader, Module, ModuleHeader, Operand},
+    grammar::{
+        CoreInstructionTable, GlslStd450InstructionTable, LogicalOperand,
+        OpenCLStd100InstructionTable, OperandKind, OperandQuantifier,
+    },
+};
+use spirv::{Op, Word};
+use std::{collections::HashMap, str::FromStr};
+
+/// Assembles `text` into a module. Every line that cannot be assembled is
+/// reported with its line and column.
+pub fn assemble(text: &str) -> Result<Module, Diagnostics> {
+    let mut errors = Vec::new();
+    let mut version = None;
+
+    let mut lines = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index as u32 + 1;
+
+        if let Some(header_version) = line.trim().strip_prefix("; Version:") {
+            version = parse_version(header_version.trim());
+        }
+
+        match tokenize(line) {
+            Ok(tokens) if tokens.is_empty() => (),
+            Ok(tokens) => lines.push((line_number, tokens)),
+            Err(e) => errors.push(e.at_line(line_number)),
+        }
+    }
+
+    // Named ids are numbered after the highest numeric id, so numeric ids stay
+    // unchanged
+    let highest_numeric_id = lines
+        .iter()
+        .flat_map(|(_, tokens)| tokens.iter())
+        .filter_map(|token| token.text.strip_prefix('%')?.parse::<Word>().ok())
+        .max()
+        .unwrap_or(0);
+
+    let mut assembler = Assembler {
+        ids: HashMap::new(),
+        next_id: highest_numeric_id + 1,
+        number_types: HashMap::new(),
+        value_types: HashMap::new(),
+        ext_inst_sets: HashMap::new(),
+    };
+
+    let mut loader = Loader::new();
+    let mut last_line = 0;
+    for (line_number, tokens) in lines.iter() {
+        last_line = *line_number;
+
+        let instruction = match assembler.instruction(tokens) {
+            Ok(instruction) => instruction,
+            Err(e) => {
+                errors.push(e.at_line(*line_number));
+                continue;
+            },
+        };
+
+        if let ParseAction::Error(e) = loader.consume_instruction(instruction) {
+            errors.push(AssemblyError::new(tokens[0].column, e.to_string()).at_line(*line_number));
+        }
+    }
+    if let ParseAction::Error(e) = loader.finalize() {
+        errors.push(AssemblyError::new(1, e.to_string()).at_line(last_line));
+    }
+
+    if !errors.is_empty() {
+        return Err(Diagnostics {
+            error_count: errors.len() as u32,
+            warning_count: 0,
+            messages: errors,
+        });
+    }
+
+    let mut module = loader.module();
+
+    // The friendly syntax hides OpName, so names that cannot be derived from
+    // the module itself are restored from the id names
+    let derived_names = ModuleInfo::create(&module)
+        .map(|info| info.names)
+        .unwrap_or_default();
+    let mut restored_names = assembler
+        .ids
+        .iter()
+        .filter(|(_, id)| !derived_names.contains_key(id))
+        .map(|(name, id)| (*id, name.to_string()))
+        .collect::<Vec<_>>();
+    restored_names.sort_unstable();
+    for (id, name) in restored_names {
+        module.debug_names.push(Instruction::new(
+            Op::Name,
+            None,
+            None,
+            vec![Operand::IdRef(id), Operand::LiteralString(name)],
+        ));
+    }
+
+    let mut header = ModuleHeader::new(assembler.next_id);
+    if let Some((major, minor)) = version {
+        header.set_version(major, minor);
+    }
+    module.header = Some(header);
+
+    Ok(module)
+}
+
+fn parse_version(version: &str) -> Option<(u8, u8)> {
+    let (major, minor) = version.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+struct AssemblyError {
+    column: u32,
+    message: String,
+}
+
+impl AssemblyError {
+    fn new(column: u32, message: impl Into<String>) -> Self {
+        Self {
+            column,
+            message: message.into(),
+        }
+    }
+
+    fn at_line(self, line: u32) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            file: None,
+            line: Some(line),
+            column: Some(self.column),
+            message: self.message,
+        }
+    }
+}
+
+struct Token<'a> {
+    text: &'a str,
+    /// 1-based, in characters
+    column: u32,
+}
+
+/// Splits a line into whitespace separated tokens. String literals may contain
+/// whitespace, and a `;` at the start of a token begins a comment.
+///
+/// Friendly names like `%[f32;4]` contain `;`, so comments cannot start in the
+/// middle of a token.
+fn tokenize(line: &str) -> Result<Vec<Token<'_>>, AssemblyError> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().enumerate().peekable();
+
+    while let Some((column, (start, c))) = chars.next() {
+        let column = column as u32 + 1;
+
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == ';' {
+            break;
+        }
+
+        let mut end = line.len();
+        if c == '"' {
+            let mut escaped = false;
+            let mut terminated = false;
+            for (_, (index, c)) in chars.by_ref() {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => {
+                        end = index + 1;
+                        terminated = true;
+                        break;
+                    },
+                    _ => (),
+                }
+            }
+            if !terminated {
+                return Err(AssemblyError::new(column, "Unterminated string literal"));
+            }
+        } else {
+            while let Some((_, (index, c))) = chars.peek() {
+                if c.is_whitespace() {
+                    end = *index;
+                    break;
+                }
+                chars.next();
+            }
+        }
+
+        tokens.push(Token {
+            text: &line[start..end],
+            column,
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// Reads the tokens of a single line
+struct Cursor<'t, 'a> {
+    tokens: &'t [Token<'a>],
+    position: usize,
+}
+
+impl<'t, 'a> Cursor<'t, 'a> {
+    fn peek(&self) -> Option<&'t Token<'a>> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self, expected: &str) -> Result<&'t Token<'a>, AssemblyError> {
+        let token = self.peek().ok_or_else(|| {
+            let column = self
+                .tokens
+                .last()
+                .map(|token| token.column + token.text.chars().count() as u32)
+                .unwrap_or(1);
+            AssemblyError::new(column, format!("Expected {expected}"))
+        })?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+}
+
+/// The type of a numeric literal, which determines how it is encoded
+#[derive(Clone, Copy)]
+pub enum NumberType {
+    Int { width: u32, signed: bool },
+    Float { width: u32 },
+}
+
+struct Assembler<'a> {
+    ids: HashMap<&'a str, Word>,
+    next_id: Word,
+    /// Integer and float types, which determine how constants are encoded
+    number_types: HashMap<Word, NumberType>,
+    /// The result type of every value, for `OpSwitch` literals
+    value_types: HashMap<Word, Word>,
+    /// Names of the imported extended instruction sets
+    ext_inst_sets: HashMap<Word, String>,
+}
+
+impl<'a> Assembler<'a> {
+    fn instruction(&mut self, tokens: &[Token<'a>]) -> Result<Instruction, AssemblyError> {
+        let mut cursor = Cursor {
+            tokens,
+            position: 0,
+        };
+
+        let mut result_id = None;
+        if tokens[0].text.starts_with('%') {
+            result_id = Some(self.id(cursor.next("a result id")?)?);
+            if cursor.peek().map(|token| token.text) == Some("=") {
+                cursor.position += 1;
+            }
+        }
+
+        let name = cursor.next("an instruction")?;
+        let grammar = name
+            .text
+            .strip_prefix("Op")
+            .and_then(|opname| CoreInstructionTable::iter().find(|g| g.opname == opname))
+            .ok_or_else(|| {
+                AssemblyError::new(name.column, format!("Unknown instruction {}", name.text))
+            })?;
+
+        let has_result = grammar
+            .operands
+            .iter()
+            .any(|operand| operand.kind == OperandKind::IdResult);
+        match (has_result, result_id) {
+            (true, None) => {
+                return Err(AssemblyError::new(
+                    name.column,
+                    format!("{} requires a result id", name.text),
+                ))
+            },
+            (false, Some(_)) => {
+                return Err(AssemblyError::new(
+                    tokens[0].column,
+                    format!("{} has no result id", name.text),
+                ))
+            },
+            _ => (),
+        }
+
+        let mut result_type = None;
+        if grammar
+            .operands
+            .iter()
+            .any(|operand| operand.kind == OperandKind::IdResultType)
+        {
+            result_type = Some(self.id(cursor.next("a result type")?)?);
+        }
+
+        let mut operands = Vec::new();
+        self.logical_operands(&mut cursor, grammar.operands, result_type, &mut operands)?;
+
+        if let Some(token) = cursor.peek() {
+            return Err(AssemblyError::new(
+                token.column,
+                format!("Unexpected operand {} for {}", token.text, name.text),
+            ));
+        }
+
+        let instruction = Instruction::new(grammar.opcode, result_type, result_id, operands);
+        self.track(&instruction);
+        Ok(instruction)
+    }
+
+    /// Remembers what is needed to encode later literals
+    fn track(&mut self, instruction: &Instruction) {
+        let result_id = match instruction.result_id {
+            Some(result_id) => result_id,
+            None => return,
+        };
+
+        if let Some(result_type) = instruction.result_type {
+            self.value_types.insert(result_id, result_type);
+        }
+
+        match (instruction.class.opcode, instruction.operands.as_slice()) {
+            (Op::TypeInt, [Operand::LiteralBit32(width), Operand::LiteralBit32(signed), ..]) => {
+                self.number_types.insert(
+                    result_id,
+                    NumberType::Int {
+                        width: *width,
+                        signed: *signed != 0,
+                    },
+                );
+            },
+            (Op::TypeFloat, [Operand::LiteralBit32(width), ..]) => {
+                self.number_types
+                    .insert(result_id, NumberType::Float { width: *width });
+            },
+            (Op::ExtInstImport, [Operand::LiteralString(name), ..]) => {
+                self.ext_inst_sets.insert(result_id, name.clone());
+            },
+            _ => (),
+        }
+    }
+
+    fn logical_operands(
+        &mut self,
+        cursor: &mut Cursor<'_, 'a>,
+        logical_operands: &[LogicalOperand],
+        result_type: Option<Word>,
+        operands: &mut Vec<Operand>,
+    ) -> Result<(), AssemblyError> {
+        for logical_operand in logical_operands {
+            match logical_operand.kind {
+                OperandKind::IdResultType | OperandKind::IdResult => continue,
+                _ => (),
+            }
+
+            match logical_operand.quantifier {
+                OperandQuantifier::One => {
+                    self.operand(cursor, logical_operand.kind, result_type, operands)?
+                },
+                OperandQuantifier::ZeroOrOne => {
+                    if !cursor.is_empty() {
+                        self.operand(cursor, logical_operand.kind, result_type, operands)?;
+                    }
+                },
+                OperandQuantifier::ZeroOrMore => {
+                    while !cursor.is_empty() {
+                        self.operand(cursor, logical_operand.kind, result_type, operands)?;
+                    }
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    fn operand(
+        &mut self,
+        cursor: &mut Cursor<'_, 'a>,
+        kind: OperandKind,
+        result_type: Option<Word>,
+        operands: &mut Vec<Operand>,
+    ) -> Result<(), AssemblyError> {
+        macro_rules! value {
+            ($kind:ident) => {{
+                let token = cursor.next(stringify!($kind))?;
+                Operand::$kind(parse_value::<spirv::$kind>(token, stringify!($kind))?)
+            }};
+        }
+        macro_rules! flags {
+            ($kind:ident) => {{
+                let (column, text) = flags_text(cursor, stringify!($kind))?;
+                spirv::$kind::from_bits_truncate(parse_flags::<spirv::$kind>(
+                    column,
+                    &text,
+                    stringify!($kind),
+                )?)
+            }};
+        }
+
+        let operand = match kind {
+            OperandKind::IdRef => Operand::IdRef(self.id(cursor.next("an id")?)?),
+            OperandKind::IdScope => Operand::IdScope(self.id(cursor.next("a scope id")?)?),
+            OperandKind::IdMemorySemantics => {
+                Operand::IdMemorySemantics(self.id(cursor.next("a memory semantics id")?)?)
+            },
+            OperandKind::IdResultType | OperandKind::IdResult => {
+                unreachable!("result ids are parsed before the operands")
+            },
+
+            OperandKind::LiteralInteger => {
+                Operand::LiteralBit32(parse_word(cursor.next("an integer")?)?)
+            },
+            OperandKind::LiteralString => {
+                Operand::LiteralString(parse_string(cursor.next("a string")?)?)
+            },
+            OperandKind::LiteralFloat => {
+                let token = cursor.next("a float")?;
+                parse_number(token, NumberType::Float { width: 32 })?
+            },
+            OperandKind::LiteralContextDependentNumber => {
+                let token = cursor.next("a number")?;
+                let number_type = result_type
+                    .and_then(|result_type| self.number_types.get(&result_type))
+                    .copied()
+                    .ok_or_else(|| {
+                        AssemblyError::new(
+                            token.column,
+                            "Constant has no preceding integer or float type",
+                        )
+                    })?;
+                parse_number(token, number_type)?
+            },
+            OperandKind::LiteralExtInstInteger => {
+                let token = cursor.next("an extended instruction")?;
+                let set = match operands.last() {
+                    Some(Operand::IdRef(set)) => self.ext_inst_sets.get(set),
+                    _ => None,
+                };
+                Operand::LiteralExtInstInteger(ext_inst_opcode(token, set)?)
+            },
+            OperandKind::LiteralSpecConstantOpInteger => {
+                let token = cursor.next("an opcode")?;
+                let grammar = token
+                    .text
+                    .parse::<u16>()
+                    .ok()
+                    .and_then(CoreInstructionTable::lookup_opcode)
+                    .or_else(|| {
+                        let opname = token.text.strip_prefix("Op").unwrap_or(token.text);
+                        CoreInstructionTable::iter().find(|g| g.opname == opname)
+                    })
+                    .ok_or_else(|| {
+                        AssemblyError::new(token.column, format!("Unknown opcode {}", token.text))
+                    })?;
+
+                operands.push(Operand::LiteralSpecConstantOpInteger(grammar.opcode));
+                return self.logical_operands(cursor, grammar.operands, None, operands);
+            },
+
+            OperandKind::PairLiteralIntegerIdRef => {
+                // OpSwitch literals have the width of the selector
+                let token = cursor.next("a literal")?;
+                let number_type = match operands.first() {
+                    Some(Operand::IdRef(selector)) => self
+                        .value_types
+                        .get(selector)
+                        .and_then(|selector_type| self.number_types.get(selector_type))
+                        .copied(),
+                    _ => None,
+                };
+                operands.push(match number_type {
+                    Some(number_type) => parse_number(token, number_type)?,
+                    None => Operand::LiteralBit32(parse_word(token)?),
+                });
+                Operand::IdRef(self.id(cursor.next("a label")?)?)
+            },
+            OperandKind::PairIdRefLiteralInteger => {
+                operands.push(Operand::IdRef(self.id(cursor.next("an id")?)?));
+                Operand::LiteralBit32(parse_word(cursor.next("an integer")?)?)
+            },
+            OperandKind::PairIdRefIdRef => {
+                operands.push(Operand::IdRef(self.id(cursor.next("an id")?)?));
+                Operand::IdRef(self.id(cursor.next("an id")?)?)
+            },
+
+            OperandKind::ImageOperands => {
+                let value = flags!(ImageOperands);
+                operands.push(Operand::ImageOperands(value));
+                return self.parameters(cursor, &image_operands_parameters(value), operands);
+            },
+            OperandKind::LoopControl => {
+                let value = flags!(LoopControl);
+                operands.push(Operand::LoopControl(value));
+                return self.parameters(cursor, &loop_control_parameters(value), operands);
+            },
+            OperandKind::MemoryAccess => {
+                let value = flags!(MemoryAccess);
+                operands.push(Operand::MemoryAccess(value));
+                return self.parameters(cursor, &memory_access_parameters(value), operands);
+            },
+            OperandKind::FPFastMathMode => Operand::FPFastMathMode(flags!(FPFastMathMode)),
+            OperandKind::SelectionControl => Operand::SelectionControl(flags!(SelectionControl)),
+            OperandKind::FunctionControl => Operand::FunctionControl(flags!(FunctionControl)),
+            OperandKind::MemorySemantics => Operand::MemorySemantics(flags!(MemorySemantics)),
+            OperandKind::KernelProfilingInfo => {
+                Operand::KernelProfilingInfo(flags!(KernelProfilingInfo))
+            },
+            OperandKind::RayFlags => Operand::RayFlags(flags!(RayFlags)),
+            OperandKind::FragmentShadingRate => {
+                Operand::FragmentShadingRate(flags!(FragmentShadingRate))
+            },
+            OperandKind::CooperativeMatrixOperands => {
+                Operand::CooperativeMatrixOperands(flags!(CooperativeMatrixOperands))
+            },
+
+            OperandKind::ExecutionMode => {
+                let token = cursor.next("an execution mode")?;
+                let value = parse_value::<spirv::ExecutionMode>(token, "ExecutionMode")?;
+                operands.push(Operand::ExecutionMode(value));
+                return self.parameters(cursor, &execution_mode_parameters(value), operands);
+            },
+            OperandKind::Decoration => {
+                let token = cursor.next("a decoration")?;
+                let value = parse_value::<spirv::Decoration>(token, "Decoration")?;
+                operands.push(Operand::Decoration(value));
+                return self.parameters(cursor, &decoration_parameters(value), operands);
+            },
+            OperandKind::Dim => {
+                // Dimensions are written without their prefix, e.g. 2D
+                let token = cursor.next("a dimension")?;
+                let value = format!("Dim{}", token.text)
+                    .parse::<spirv::Dim>()
+                    .or_else(|_| parse_value::<spirv::Dim>(token, "Dim"))?;
+                Operand::Dim(value)
+            },
+            OperandKind::SourceLanguage => value!(SourceLanguage),
+            OperandKind::ExecutionModel => value!(ExecutionModel),
+            OperandKind::AddressingModel => value!(AddressingModel),
+            OperandKind::MemoryModel => value!(MemoryModel),
+            OperandKind::StorageClass => value!(StorageClass),
+            OperandKind::SamplerAddressingMode => value!(SamplerAddressingMode),
+            OperandKind::SamplerFilterMode => value!(SamplerFilterMode),
+            OperandKind::ImageFormat => value!(ImageFormat),
+            OperandKind::ImageChannelOrder => value!(ImageChannelOrder),
+            OperandKind::ImageChannelDataType => value!(ImageChannelDataType),
+            OperandKind::FPRoundingMode => value!(FPRoundingMode),
+            OperandKind::FPDenormMode => value!(FPDenormMode),
+            OperandKind::QuantizationModes => value!(QuantizationModes),
+            OperandKind::FPOperationMode => value!(FPOperationMode),
+            OperandKind::OverflowModes => value!(OverflowModes),
+            OperandKind::LinkageType => value!(LinkageType),
+            OperandKind::AccessQualifier => value!(AccessQualifier),
+            OperandKind::HostAccessQualifier => value!(HostAccessQualifier),
+            OperandKind::FunctionParameterAttribute => value!(FunctionParameterAttribute),
+            OperandKind::BuiltIn => value!(BuiltIn),
+            OperandKind::Scope => value!(Scope),
+            OperandKind::GroupOperation => value!(GroupOperation),
+            OperandKind::KernelEnqueueFlags => value!(KernelEnqueueFlags),
+            OperandKind::Capability => value!(Capability),
+            OperandKind::RayQueryIntersection => value!(RayQueryIntersection),
+            OperandKind::RayQueryCommittedIntersectionType => {
+                value!(RayQueryCommittedIntersectionType)
+            },
+            OperandKind::RayQueryCandidateIntersectionType => {
+                value!(RayQueryCandidateIntersectionType)
+            },
+            OperandKind::PackedVectorFormat => value!(PackedVectorFormat),
+            OperandKind::CooperativeMatrixLayout => value!(CooperativeMatrixLayout),
+            OperandKind::CooperativeMatrixUse => value!(CooperativeMatrixUse),
+            OperandKind::InitializationModeQualifier => value!(InitializationModeQualifier),
+            OperandKind::LoadCacheControl => value!(LoadCacheControl),
+            OperandKind::StoreCacheControl => value!(StoreCacheControl),
+        };
+
+        operands.push(operand);
+        Ok(())
+    }
+
+    /// Parses the extra operands required by a decoration, execution mode or
+    /// flag
+    fn parameters(
+        &mut self,
+        cursor: &mut Cursor<'_, 'a>,
+        kinds: &[OperandKind],
+        operands: &mut Vec<Operand>,
+    ) -> Result<(), AssemblyError> {
+        for kind in kinds {
+            self.operand(cursor, *kind, None, operands)?;
+        }
+        Ok(())
+    }
+
+    fn id(&mut self, token: &Token<'a>) -> Result<Word, AssemblyError> {
+        let name = token.text.strip_prefix('%').ok_or_else(|| {
+            AssemblyError::new(
+                token.column,
+                format!("Expected an id starting with %, found {}", token.text),
+            )
+        })?;
+
+        if let Ok(id) = name.parse::<Word>() {
+            if id == 0 {
+                return Err(AssemblyError::new(token.column, "Id 0 is not valid"));
+            }
+            return Ok(id);
+        }
+        if name.is_empty() {
+            return Err(AssemblyError::new(
+                token.column,
+                "Expected an id name after %",
+            ));
+        }
+
+        let next_id = &mut self.next_id;
+        Ok(*self.ids.entry(name).or_insert_with(|| {
+            let id = *next_id;
+            *next_id += 1;
+            id
+        }))
+    }
+}
+
+fn parse_value<T: FromStr>(token: &Token, kind: &str) -> Result<T, AssemblyError> {
+    token
+        .text
+        .parse()
+        .map_err(|_| AssemblyError::new(token.column, format!("Unknown {kind} {}", token.text)))
+}
+
+/// Collects a flag combination, which may be written as `A|B` or `A | B`
+fn flags_text(cursor: &mut Cursor, kind: &str) -> Result<(u32, String), AssemblyError> {
+    let first = cursor.next(kind)?;
+    let mut text = first.text.to_string();
+
+    while let Some(token) = cursor.peek() {
+        if !(text.ends_with('|') || token.text.starts_with('|')) {
+            break;
+        }
+        text.push_str(token.text);
+        cursor.position += 1;
+    }
+
+    Ok((first.column, text))
+}
+
+/// Parses flags by name, accepting both the constant names of the bitflags
+/// (`DONT_INLINE`) and the grammar names (`DontInline`)
+fn parse_flags<T: Flags<Bits = u32>>(
+    column: u32,
+    text: &str,
+    kind: &str,
+) -> Result<u32, AssemblyError> {
+    let normalize = |name: &str| name.replace('_', "").to_lowercase();
+
+    let mut bits = 0;
+    for name in text
+        .split('|')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        if let Some(value) = parse_integer(name).and_then(|value| u32::try_from(value).ok()) {
+            bits |= value;
+            continue;
+        }
+
+        let name = normalize(name);
+        let flag = T::FLAGS
+            .iter()
+            .find(|flag| normalize(flag.name()) == name)
+            .ok_or_else(|| AssemblyError::new(column, format!("Unknown {kind} flag {text}")))?;
+        bits |= flag.value().bits();
+    }
+
+    Ok(bits)
+}
+
+/// Parses decimal or `0x` prefixed hexadecimal integers
+fn parse_integer(text: &str) -> Option<i128> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+
+    let value = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => i128::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<i128>().ok()?,
+    };
+
+    Some(if negative { -value } else { value })
+}
+
+/// A literal that fits into one word, negative values are stored as their two's
+/// complement
+fn parse_word(token: &Token) -> Result<u32, AssemblyError> {
+    parse_integer(token.text)
+        .filter(|value| (i32::MIN as i128..=u32::MAX as i128).contains(value))
+        .map(|value| value as u32)
+        .ok_or_else(|| {
+            AssemblyError::new(
+                token.column,
+                format!("Expected a 32 bit integer, found {}", token.text),
+            )
+        })
+}
+
+fn parse_number(token: &Token, number_type: NumberType) -> Result<Operand, AssemblyError> {
+    number_literal(token.text, number_type).ok_or_else(|| {
+        let expected = match number_type {
+            NumberType::Int {
+                width,
+                signed: true,
+            } => format!("i{width}"),
+            NumberType::Int { width, .. } => format!("u{width}"),
+            NumberType::Float { width } => format!("f{width}"),
+        };
+        AssemblyError::new(
+            token.column,
+            format!("{} is not a valid {expected} literal", token.text),
+        )
+    })
+}
+
+/// Encodes a literal the way rspirv loads it for the given type, or `None` if
+/// it does not fit
+pub fn number_literal(text: &str, number_type: NumberType) -> Option<Operand> {
+    // Hexadecimal literals are taken as the bit pattern
+    let hex_bits = || {
+        let hex = text.strip_prefix("0x")?;
+        u64::from_str_radix(hex, 16).ok()
+    };
+
+    match number_type {
+        NumberType::Int { width, signed } => {
+            if width == 0 || width > 64 {
+                return None;
+            }
+            let value = parse_integer(text)?;
+            let min = if signed { -(1i128 << (width - 1)) } else { 0 };
+            if value < min || value >= 1i128 << width {
+                return None;
+            }
+
+            // Values above the signed range are bit patterns. Narrower signed
+            // types are sign extended to the full word
+            let shift = 64 - width;
+            let bits = if signed {
+                (((value as u64) << shift) as i64 >> shift) as u64
+            } else {
+                value as u64
+            };
+
+            Some(if width > 32 {
+                Operand::LiteralBit64(bits)
+            } else {
+                Operand::LiteralBit32(bits as u32)
+            })
+        },
+        NumberType::Float { width: 64 } => Some(Operand::LiteralBit64(match hex_bits() {
+            Some(bits) => bits,
+            None => text.parse::<f64>().ok()?.to_bits(),
+        })),
+        NumberType::Float { width: 32 } => Some(Operand::LiteralBit32(match hex_bits() {
+            Some(bits) => u32::try_from(bits).ok()?,
+            None => text.parse::<f32>().ok()?.to_bits(),
+        })),
+        // Half floats take the low bits of a word
+        NumberType::Float { width: 16 } => {
+            let bits = match hex_bits() {
+                Some(bits) => u16::try_from(bits).ok()?,
+                None => f16_bits(text.parse().ok()?),
+            };
+            Some(Operand::LiteralBit32(bits as u32))
+        },
+        NumberType::Float { .. } => None,
+    }
+}
+
+/// Converts to half precision, rounding towards zero
+fn f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        sign | 0x7c00
+    } else if exponent <= 0 {
+        // Subnormal, or too small and flushed to zero
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        sign | (mantissa >> (14 - exponent)) as u16
+    } else {
+        sign | (exponent as u16) << 10 | (mantissa >> 13) as u16
+    }
+}
+
+/// Parses a quoted string with `\"` and `\\` escapes, as well as the escapes
+/// produced by Rust's `Debug`
+fn parse_string(token: &Token) -> Result<String, AssemblyError> {
+    let invalid = |message: &str| AssemblyError::new(token.column, message);
+
+    let quoted = token
+        .text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .ok_or_else(|| invalid("Expected a string in double quotes"))?;
+
+    let mut string = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            string.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => string.push('\n'),
+            Some('r') => string.push('\r'),
+            Some('t') => string.push('\t'),
+            Some('0') => string.push('\0'),
+            Some('u') => {
+                let code = chars
+                    .by_ref()
+                    .skip_while(|c| *c == '{')
+                    .take_while(|c| *c != '}')
+                    .collect::<String>();
+                let c = u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| invalid("Invalid unicode escape"))?;
+                string.push(c);
+            },
+            Some(c) => string.push(c),
+            None => return Err(invalid("Unterminated escape sequence")),
+        }
+    }
+
+    Ok(string)
+}
+
+/// Extended instructions are given by number or by their name in
+/// `GLSL.std.450` or `OpenCL.std`
+fn ext_inst_opcode(token: &Token, set: Option<&String>) -> Result<u32, AssemblyError> {
+    if let Some(opcode) = parse_integer(token.text).and_then(|value| u32::try_from(value).ok()) {
+        return Ok(opcode);
+    }
+
+    let opcode = match set.map(|set| set.as_str()) {
+        Some("GLSL.std.450") => GlslStd450InstructionTable::iter()
+            .find(|g| g.opname == token.text)
+            .map(|g| g.opcode),
+        Some("OpenCL.std") => OpenCLStd100InstructionTable::iter()
+            .find(|g| g.opname == token.text)
+            .map(|g| g.opcode),
+        _ => None,
+    };
+
+    opcode.ok_or_else(|| {
+        AssemblyError::new(
+            token.column,
+            format!("Unknown extended instruction {}", token.text),
+        )
+    })
+}
+
+fn image_operands_parameters(value: spirv::ImageOperands) -> Vec<OperandKind> {
+    use spirv::ImageOperands as I;
+
+    let mut kinds = Vec::new();
+    for (flag, parameters) in [
+        (I::BIAS, &[OperandKind::IdRef][..]),
+        (I::LOD, &[OperandKind::IdRef]),
+        (I::GRAD, &[OperandKind::IdRef, OperandKind::IdRef]),
+        (I::CONST_OFFSET, &[OperandKind::IdRef]),
+        (I::OFFSET, &[OperandKind::IdRef]),
+        (I::CONST_OFFSETS, &[OperandKind::IdRef]),
+        (I::SAMPLE, &[OperandKind::IdRef]),
+        (I::MIN_LOD, &[OperandKind::IdRef]),
+        (I::MAKE_TEXEL_AVAILABLE, &[OperandKind::IdScope]),
+        (I::MAKE_TEXEL_VISIBLE, &[OperandKind::IdScope]),
+    ] {
+        if value.contains(flag) {
+            kinds.extend_from_slice(parameters);
+        }
+    }
+    kinds
+}
+
+fn loop_control_parameters(value: spirv::LoopControl) -> Vec<OperandKind> {
+    use spirv::LoopControl as L;
+
+    [
+        L::DEPENDENCY_LENGTH,
+        L::MIN_ITERATIONS,
+        L::MAX_ITERATIONS,
+        L::ITERATION_MULTIPLE,
+        L::PEEL_COUNT,
+        L::PARTIAL_COUNT,
+        L::INITIATION_INTERVAL_INTEL,
+        L::MAX_CONCURRENCY_INTEL,
+        L::DEPENDENCY_ARRAY_INTEL,
+        L::PIPELINE_ENABLE_INTEL,
+        L::LOOP_COALESCE_INTEL,
+        L::MAX_INTERLEAVING_INTEL,
+        L::SPECULATED_ITERATIONS_INTEL,
+    ]
+    .into_iter()
+    .filter(|flag| value.contains(*flag))
+    .map(|_| OperandKind::LiteralInteger)
+    .collect()
+}
+
+fn memory_access_parameters(value: spirv::MemoryAccess) -> Vec<OperandKind> {
+    use spirv::MemoryAccess as M;
+
+    let mut kinds = Vec::new();
+    if value.contains(M::ALIGNED) {
+        kinds.push(OperandKind::LiteralInteger);
+    }
+    if value.contains(M::MAKE_POINTER_AVAILABLE) {
+        kinds.push(OperandKind::IdScope);
+    }
+    if value.contains(M::MAKE_POINTER_VISIBLE) {
+        kinds.push(OperandKind::IdScope);
+    }
+    kinds
+}
+
+fn execution_mode_parameters(value: spirv::ExecutionMode) -> Vec<OperandKind> {
+    use spirv::ExecutionMode as E;
+    use OperandKind::{IdRef, LiteralInteger};
+
+    match value {
+        E::LocalSize | E::LocalSizeHint | E::MaxWorkgroupSizeINTEL => vec![LiteralInteger; 3],
+        E::LocalSizeId => vec![IdRef; 3],
+        E::SubgroupsPerWorkgroupId | E::LocalSizeHintId => vec![IdRef],
+        E::Invocations
+        | E::OutputVertices
+        | E::VecTypeHint
+        | E::SubgroupSize
+        | E::SubgroupsPerWorkgroup
+        | E::DenormPreserve
+        | E::DenormFlushToZero
+        | E::SignedZeroInfNanPreserve
+        | E::RoundingModeRTE
+        | E::RoundingModeRTZ
+        | E::OutputPrimitivesNV
+        | E::MaxWorkDimINTEL
+        | E::NumSIMDWorkitemsINTEL => vec![LiteralInteger],
+        _ => Vec::new(),
+    }
+}
+
+fn decoration_parameters(value: spirv::Decoration) -> Vec<OperandKind> {
+    use spirv::Decoration as D;
+    use OperandKind::*;
+
+    match value {
+        D::BuiltIn => vec![BuiltIn],
+        D::FuncParamAttr => vec![FunctionParameterAttribute],
+        D::FPRoundingMode => vec![FPRoundingMode],
+        D::FPFastMathMode => vec![FPFastMathMode],
+        D::LinkageAttributes => vec![LiteralString, LinkageType],
+        D::UniformId => vec![IdScope],
+        D::AlignmentId | D::MaxByteOffsetId | D::CounterBuffer => vec![IdRef],
+        D::UserSemantic | D::UserTypeGOOGLE | D::MemoryINTEL => vec![LiteralString],
+        D::MergeINTEL => vec![LiteralString, LiteralString],
+        D::SpecId
+        | D::ArrayStride
+        | D::MatrixStride
+        | D::Stream
+        | D::Location
+        | D::Component
+        | D::Index
+        | D::Binding
+        | D::DescriptorSet
+        | D::Offset
+        | D::XfbBuffer
+        | D::XfbStride
+        | D::InputAttachmentIndex
+        | D::Alignment
+        | D::MaxByteOffset
+        | D::SecondaryViewportRelativeNV
+        | D::NumbanksINTEL
+        | D::BankwidthINTEL
+        | D::MaxPrivateCopiesINTEL
+        | D::MaxReplicatesINTEL
+        | D::BankBitsINTEL
+        | D::ForcePow2DepthINTEL => vec![LiteralInteger],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rspirv::binary::Assemble;
+
+    /// Exercises numbers of every width, flags with parameters, extended
+    /// instructions, strings and spec constant operations
+    const SHADER: &str = r#"; SPIR-V
+; Version: 1.3
+               OpCapability Shader
+               OpCapability Float16
+               OpCapability Float64
+               OpCapability Int64
+               OpCapability Int16
+         %glsl = OpExtInstImport "GLSL.std.450"
+               OpMemoryModel Logical GLSL450
+               OpEntryPoint GLCompute %main "main"
+               OpExecutionMode %main LocalSize 8 1 1
+       %source = OpString "a \"quoted\" path\\with spaces"
+               OpName %counter "counter"
+               OpDecorate %size SpecId 3
+         %void = OpTypeVoid
+      %fn_void = OpTypeFunction %void
+         %bool = OpTypeBool
+          %int = OpTypeInt 32 1
+         %uint = OpTypeInt 32 0
+        %short = OpTypeInt 16 1
+         %long = OpTypeInt 64 1
+        %float = OpTypeFloat 32
+         %half = OpTypeFloat 16
+       %double = OpTypeFloat 64
+      %ptr_int = OpTypePointer Function %int
+       %int_m3 = OpConstant %int -3
+      %int_hex = OpConstant %int 0x7fffffff
+     %short_m1 = OpConstant %short -1
+      %long_m3 = OpConstant %long -3
+     %long_big = OpConstant %long 0x123456789
+    %float_one = OpConstant %float 1
+   %float_bits = OpConstant %float 0x3f000000
+    %half_half = OpConstant %half 0.5
+   %double_neg = OpConstant %double -2.25
+         %size = OpSpecConstant %int 16
+          %sum = OpSpecConstantOp %int IAdd %size %int_m3
+         %main = OpFunction %void Inline|DontInline %fn_void
+        %entry = OpLabel
+      %counter = OpVariable %ptr_int Function
+                 OpStore %counter %int_m3 Aligned 4
+                 OpBranch %header
+       %header = OpLabel
+                 OpLoopMerge %merge %continue Unroll | DependencyLength 4
+                 OpBranch %body
+         %body = OpLabel
+        %value = OpLoad %int %counter
+         %cond = OpSLessThan %bool %value %sum
+                 OpSelectionMerge %continue None
+                 OpSwitch %value %continue -1 %merge 0x10 %merge
+     %continue = OpLabel
+         %root = OpExtInst %float %glsl Sqrt %float_one
+                 OpBranch %header
+        %merge = OpLabel
+                 OpReturn
+                 OpFunctionEnd
+"#;
+
+    fn assemble_ok(text: &str) -> Module {
+        assemble(text).unwrap_or_else(|e| panic!("{:#?}", e.messages))
+    }
+
+    fn words(module: &Module) -> Vec<Word> {
+        module.assemble()
+    }
+
+    /// The friendly disassembly, as shown in the user interface
+    fn friendly(module: &Module) -> String {
+        let assembly = crate::annotate_module(module, None, "", &Diagnostics::default()).unwrap();
+        let version = module.header.as_ref().unwrap().version();
+        let mut text = format!("; Version: {}.{}\n", version.0, version.1);
+        for instruction in assembly.instructions.iter() {
+            text.push_str(&instruction.instruction);
+            text.push('\n');
+        }
+        text
+    }
+
+    fn errors(text: &str) -> Vec<(Option<u32>, Option<u32>, String)> {
+        assemble(text)
+            .expect_err("the text should not assemble")
+            .messages
+            .into_iter()
+            .map(|message| (message.line, message.column, message.message))
+            .collect()
+    }
+
+    fn instruction(module: &Module, opcode: Op) -> &Instruction {
+        module
+            .all_inst_iter()
+            .find(|instruction| instruction.class.opcode == opcode)
+            .unwrap()
+    }
+
+    #[test]
+    fn friendly_round_trip_keeps_the_words() {
+        // Named ids are numbered in order of appearance, so the first round trip
+        // settles the numbering and every following one is exact
+        let module = assemble_ok(&friendly(&assemble_ok(SHADER)));
+        let text = friendly(&module);
+        let again = assemble_ok(&text);
+
+        assert_eq!(words(&module), words(&again));
+        assert_eq!(text, friendly(&again));
+    }
+
+    #[test]
+    fn friendly_round_trip_keeps_the_instructions() {
+        let original = assemble_ok(SHADER);
+        let round_trip = assemble_ok(&friendly(&original));
+
+        // The ids and the order of the OpNames differ, but nothing else may
+        let shape = |module: &Module| {
+            module
+                .all_inst_iter()
+                .filter(|instruction| instruction.class.opcode != Op::Name)
+                .map(|instruction| {
+                    let literals = instruction
+                        .operands
+                        .iter()
+                        .filter(|operand| !matches!(operand, Operand::IdRef(_)))
+                        .map(|operand| format!("{operand:?}"))
+                        .collect::<Vec<_>>();
+                    (instruction.class.opcode, literals)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shape(&original), shape(&round_trip));
+        assert_eq!(words(&original).len(), words(&round_trip).len());
+    }
+
+    #[test]
+    fn spirv_as_numeric_ids_are_kept() {
+        let module = assemble_ok(
+            "; Version: 1.0\n\
+             OpCapability Shader\n\
+             OpMemoryModel Logical GLSL450\n\
+             %1 = OpTypeVoid\n\
+             %7 = OpTypeInt 32 1\n\
+             %9 = OpConstant %7 -2\n",
+        );
+        assert_eq!(
+            words(&module),
+            [
+                spirv::MAGIC_NUMBER,
+                0x0001_0000,
+                // rspirv's generator id
+                0x000f_0000,
+                10,
+                0,
+                // OpCapability Shader
+                0x0002_0011,
+                1,
+                // OpMemoryModel Logical GLSL450
+                0x0003_000e,
+                0,
+                1,
+                // %1 = OpTypeVoid
+                0x0002_0013,
+                1,
+                // %7 = OpTypeInt 32 1
+                0x0004_0015,
+                7,
+                32,
+                1,
+                // %9 = OpConstant %7 -2
+                0x0004_002b,
+                7,
+                9,
+                0xffff_fffe,
+            ]
+        );
+    }
+
+    #[test]
+    fn both_syntaxes_assemble_to_the_same_words() {
+        let spirv_as = assemble_ok(SHADER);
+        let friendly = assemble_ok(&SHADER.replace(" = ", " "));
+        assert_eq!(words(&spirv_as), words(&friendly));
+    }
+
+    #[test]
+    fn flag_combinations() {
+        let function_control = |text: &str| {
+            parse_flags::<spirv::FunctionControl>(1, text, "FunctionControl").map_err(|e| e.message)
+        };
+        let both = (spirv::FunctionControl::INLINE | spirv::FunctionControl::DONT_INLINE).bits();
+
+        assert_eq!(function_control("Inline|DontInline"), Ok(both));
+        assert_eq!(function_control("Inline | DontInline"), Ok(both));
+        assert_eq!(function_control("INLINE|DONT_INLINE"), Ok(both));
+        assert_eq!(function_control("None"), Ok(0));
+        assert_eq!(function_control("NONE"), Ok(0));
+        assert_eq!(function_control(""), Ok(0));
+        assert_eq!(function_control("0x3"), Ok(both));
+        assert_eq!(
+            function_control("Inline|Sometimes"),
+            Err("Unknown FunctionControl flag Inline|Sometimes".to_string())
+        );
+    }
+
+    #[test]
+    fn flag_combinations_in_instructions() {
+        let module = assemble_ok(SHADER);
+
+        let function = instruction(&module, Op::Function);
+        assert_eq!(
+            function.operands[0],
+            Operand::FunctionControl(
+                spirv::FunctionControl::INLINE | spirv::FunctionControl::DONT_INLINE
+            )
+        );
+
+        // Spaced flags are joined, followed by the parameter of DependencyLength
+        let loop_merge = instruction(&module, Op::LoopMerge);
+        assert_eq!(
+            loop_merge.operands[2..],
+            [
+                Operand::LoopControl(
+                    spirv::LoopControl::UNROLL | spirv::LoopControl::DEPENDENCY_LENGTH
+                ),
+                Operand::LiteralBit32(4),
+            ]
+        );
+
+        let selection_merge = instruction(&module, Op::SelectionMerge);
+        assert_eq!(
+            selection_merge.operands[1],
+            Operand::SelectionControl(spirv::SelectionControl::NONE)
+        );
+
+        let store = instruction(&module, Op::Store);
+        assert_eq!(
+            store.operands[2..],
+            [
+                Operand::MemoryAccess(spirv::MemoryAccess::ALIGNED),
+                Operand::LiteralBit32(4),
+            ]
+        );
+
+        // Optional flags may be left out entirely
+        let load = instruction(&module, Op::Load);
+        assert_eq!(load.operands.len(), 1);
+    }
+
+    #[test]
+    fn negative_and_hex_literals() {
+        let int = |width, signed| NumberType::Int { width, signed };
+
+        assert_eq!(
+            number_literal("-3", int(32, true)),
+            Some(Operand::LiteralBit32(0xffff_fffd))
+        );
+        assert_eq!(
+            number_literal("-1", int(16, true)),
+            Some(Operand::LiteralBit32(0xffff_ffff))
+        );
+        assert_eq!(
+            number_literal("-128", int(8, true)),
+            Some(Operand::LiteralBit32(0xffff_ff80))
+        );
+        assert_eq!(
+            number_literal("-3", int(64, true)),
+            Some(Operand::LiteralBit64(0xffff_ffff_ffff_fffd))
+        );
+        // Above the signed range, values are bit patterns and sign extended
+        // just the same
+        assert_eq!(
+            number_literal("128", int(8, true)),
+            Some(Operand::LiteralBit32(0xffff_ff80))
+        );
+        assert_eq!(
+            number_literal("0xffff", int(16, true)),
+            Some(Operand::LiteralBit32(0xffff_ffff))
+        );
+        assert_eq!(
+            number_literal("0xffff", int(16, false)),
+            Some(Operand::LiteralBit32(0xffff))
+        );
+        assert_eq!(
+            number_literal("0xff", int(32, false)),
+            Some(Operand::LiteralBit32(255))
+        );
+        assert_eq!(
+            number_literal("0x123456789", int(64, false)),
+            Some(Operand::LiteralBit64(0x1_2345_6789))
+        );
+        assert_eq!(
+            number_literal("-0x10", int(32, true)),
+            Some(Operand::LiteralBit32(-16i32 as u32))
+        );
+
+        // Out of range for the type
+        assert_eq!(number_literal("-1", int(32, false)), None);
+        assert_eq!(number_literal("256", int(8, true)), None);
+        assert_eq!(number_literal("-129", int(8, true)), None);
+        assert_eq!(number_literal("0x100000000", int(32, false)), None);
+        assert_eq!(number_literal("1.5", int(32, true)), None);
+
+        // Hexadecimal floats are bit patterns
+        assert_eq!(
+            number_literal("0x3f800000", NumberType::Float { width: 32 }),
+            Some(Operand::LiteralBit32(1.0f32.to_bits()))
+        );
+        assert_eq!(
+            number_literal("-2.25", NumberType::Float { width: 64 }),
+            Some(Operand::LiteralBit64((-2.25f64).to_bits()))
+        );
+        assert_eq!(number_literal("1", NumberType::Float { width: 8 }), None);
+    }
+
+    #[test]
+    fn negative_and_hex_literals_in_instructions() {
+        let module = assemble_ok(SHADER);
+        let constants = module
+            .types_global_values
+            .iter()
+            .filter(|instruction| instruction.class.opcode == Op::Constant)
+            .map(|instruction| instruction.operands[0].clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            constants,
+            [
+                Operand::LiteralBit32(0xffff_fffd),
+                Operand::LiteralBit32(0x7fff_ffff),
+                Operand::LiteralBit32(0xffff_ffff),
+                Operand::LiteralBit64(0xffff_ffff_ffff_fffd),
+                Operand::LiteralBit64(0x1_2345_6789),
+                Operand::LiteralBit32(1.0f32.to_bits()),
+                Operand::LiteralBit32(0.5f32.to_bits()),
+                Operand::LiteralBit32(0x3800),
+                Operand::LiteralBit64((-2.25f64).to_bits()),
+            ]
+        );
+
+        // Switch literals take the width of the selector
+        let switch = instruction(&module, Op::Switch);
+        assert_eq!(switch.operands[2], Operand::LiteralBit32(0xffff_ffff));
+        assert_eq!(switch.operands[4], Operand::LiteralBit32(0x10));
+    }
+
+    #[test]
+    fn f16_literals() {
+        let half = |text: &str| match number_literal(text, NumberType::Float { width: 16 }) {
+            Some(Operand::LiteralBit32(bits)) => Some(bits),
+            _ => None,
+        };
+
+        assert_eq!(half("1"), Some(0x3c00));
+        assert_eq!(half("0.5"), Some(0x3800));
+        assert_eq!(half("-2"), Some(0xc000));
+        assert_eq!(half("0"), Some(0x0000));
+        assert_eq!(half("-0"), Some(0x8000));
+        assert_eq!(half("65504"), Some(0x7bff));
+        // Smallest subnormal and the largest one
+        assert_eq!(half("5.960464477539063e-8"), Some(0x0001));
+        assert_eq!(half("6.097555160522461e-5"), Some(0x03ff));
+        // Too small for a subnormal, and too large for a normal number
+        assert_eq!(half("1e-10"), Some(0x0000));
+        assert_eq!(half("1e6"), Some(0x7c00));
+        assert_eq!(half("-inf"), Some(0xfc00));
+        assert_eq!(half("NaN"), Some(0x7e00));
+        // Bit patterns are taken as they are, but must fit into 16 bits
+        assert_eq!(half("0x3555"), Some(0x3555));
+        assert_eq!(half("0x10000"), None);
+        assert_eq!(half("half"), None);
+    }
+
+    #[test]
+    fn string_escapes() {
+        let string = |text: &str| parse_string(&Token { text, column: 1 }).map_err(|e| e.message);
+
+        assert_eq!(string(r#""plain""#), Ok("plain".to_string()));
+        assert_eq!(
+            string(r#""a \"quoted\" \\ path""#),
+            Ok(r#"a "quoted" \ path"#.to_string())
+        );
+        assert_eq!(string(r#""\n\r\t\0""#), Ok("\n\r\t\0".to_string()));
+        assert_eq!(string(r#""\u{e9}\u{1F600}""#), Ok("é😀".to_string()));
+        assert_eq!(string(r#""\q""#), Ok("q".to_string()));
+        assert_eq!(
+            string(r#""\u{110000}""#),
+            Err("Invalid unicode escape".to_string())
+        );
+        assert_eq!(
+            string("unquoted"),
+            Err("Expected a string in double quotes".to_string())
+        );
+
+        // Debug formatting, as used by the disassembly, parses back
+        let original = "tab\t \"quote\" back\\slash é";
+        assert_eq!(string(&format!("{original:?}")), Ok(original.to_string()));
+    }
+
+    #[test]
+    fn strings_may_contain_whitespace_and_semicolons() {
+        let module = assemble_ok(
+            "OpCapability Shader\n\
+             %1 = OpString \"a ; b\" ; a comment\n",
+        );
+        assert_eq!(
+            instruction(&module, Op::String).operands,
+            [Operand::LiteralString("a ; b".to_string())]
+        );
+        assert_eq!(
+            instruction(&assemble_ok(SHADER), Op::String).operands,
+            [Operand::LiteralString(
+                r#"a "quoted" path\with spaces"#.to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn names_are_restored() {
+        let module = assemble_ok(SHADER);
+        let mut names = module
+            .debug_names
+            .iter()
+            .map(|instruction| match instruction.operands.as_slice() {
+                [Operand::IdRef(_), Operand::LiteralString(name)] => name.as_str(),
+                _ => panic!("not an OpName"),
+            })
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+
+        // Names derived from the instructions, like %int or %fn_void, are not
+        // restored. Neither is `counter`, which already has an OpName
+        assert_eq!(
+            names,
+            [
+                "body", "cond", "continue", "counter", "entry", "glsl", "header", "main", "merge",
+                "root", "source", "value"
+            ]
+        );
+    }
+
+    #[test]
+    fn restored_names_survive_the_friendly_round_trip() {
+        let module = assemble_ok(SHADER);
+        let info = ModuleInfo::create(&module).unwrap();
+        let again = assemble_ok(&friendly(&module));
+        let info_again = ModuleInfo::create(&again).unwrap();
+
+        let mut names = info.names.values().collect::<Vec<_>>();
+        let mut names_again = info_again.names.values().collect::<Vec<_>>();
+        names.sort_unstable();
+        names_again.sort_unstable();
+        assert_eq!(names, names_again);
+    }
+
+    #[test]
+    fn errors_report_line_and_column() {
+        assert_eq!(
+            errors("OpCapability Shader\n   OpFoo %1\n"),
+            [(Some(2), Some(4), "Unknown instruction OpFoo".to_string())]
+        );
+        assert_eq!(
+            errors("%1 = OpString \"open"),
+            [(Some(1), Some(15), "Unterminated string literal".to_string())]
+        );
+        assert_eq!(
+            errors("OpCapability Shader\n%1 = OpTypeInt 32"),
+            [(Some(2), Some(18), "Expected an integer".to_string())]
+        );
+        assert_eq!(
+            errors("%1 = OpTypeInt 32 1\n%2 = OpConstant %1 1.5"),
+            [(
+                Some(2),
+                Some(20),
+                "1.5 is not a valid i32 literal".to_string()
+            )]
+        );
+        assert_eq!(
+            errors("OpCapability Shader Extra"),
+            [(
+                Some(1),
+                Some(21),
+                "Unexpected operand Extra for OpCapability".to_string()
+            )]
+        );
+        assert_eq!(
+            errors("OpTypeVoid"),
+            [(
+                Some(1),
+                Some(1),
+                "OpTypeVoid requires a result id".to_string()
+            )]
+        );
+        assert_eq!(
+            errors("%1 = OpConstant %2 1"),
+            [(
+                Some(1),
+                Some(20),
+                "Constant has no preceding integer or float type".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn every_bad_line_is_reported() {
+        let errors = errors("OpFoo\nOpCapability Shader\nOpCapability Nothing\n%0 = OpTypeVoid");
+        let lines = errors.iter().map(|(line, ..)| *line).collect::<Vec<_>>();
+        assert_eq!(lines, [Some(1), Some(3), Some(4)]);
+        assert_eq!(errors[1].2, "Unknown Capability Nothing");
+        assert_eq!(errors[2].2, "Id 0 is not valid");
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        // The é takes two bytes, but is a single column
+        assert_eq!(
+            errors("%é = OpFoo"),
+            [(Some(1), Some(6), "Unknown instruction OpFoo".to_string())]
+        );
+    }
+}
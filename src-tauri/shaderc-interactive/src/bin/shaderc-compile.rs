@@ -0,0 +1,328 @@
+//! Compiles a shader from the command line, without the user interface
+
+use eyre::{bail, eyre, Result, WrapErr};
+use serde_json::{Map, Value};
+use shaderc_interactive::{
+    annotated_disassembly::AnnotatedDisassembly,
+    compilation::Compilation,
+    compile_shader,
+    options::{CompileShaderOptions, ShaderKind},
+    CompileError,
+};
+use std::path::Path;
+
+const USAGE: &str = "\
+Usage: shaderc-compile <FILE> [--kind <SHADER_KIND>] [--json] [OPTIONS]
+
+Compiles a shader and prints the annotated disassembly.
+
+  --kind <KIND>       Shader kind, e.g. Vertex or Fragment. Inferred from the
+                      file extension (.vert, .frag, .comp, ...) if omitted
+  --json              Print the full compilation result as JSON
+  -D NAME[=VALUE]     Define a preprocessor macro, also as -DNAME[=VALUE]
+  -I <DIR>            Add an include directory, also as -I<DIR>
+
+Every other compile option is passed as --<option-name> <value>, e.g.
+--target-env OpenGL, --optimization Performance or --spirv-version 1.6.
+Values are read as JSON where possible and as strings otherwise, so maps like
+--binding-base '{\"Texture\": 4}' can be given as well.
+";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let code = match parse_args(&args) {
+        Ok(command) => command.execute(),
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            2
+        },
+    };
+    std::process::exit(code);
+}
+
+struct CompileCommand {
+    source: String,
+    shader_kind: ShaderKind,
+    options: CompileShaderOptions,
+    json: bool,
+}
+
+impl CompileCommand {
+    fn execute(self) -> i32 {
+        let result = compile_shader(&self.source, self.shader_kind, &self.options);
+
+        if self.json {
+            let compilation = Compilation::new(result, &self.options);
+            match serde_json::to_string_pretty(&compilation) {
+                Ok(json) => println!("{json}"),
+                Err(e) => {
+                    eprintln!("Failed to serialize the result: {e}");
+                    return 1;
+                },
+            }
+
+            return match compilation {
+                Compilation::Success { .. } => 0,
+                _ => 1,
+            };
+        }
+
+        match result {
+            Ok(compiled) => {
+                eprint!("{}", compiled.warning);
+                print!("{}", format_assembly(&compiled.assembly));
+                0
+            },
+            Err(e) => {
+                if let CompileError::Analysis {
+                    warning,
+                    disassembly,
+                    ..
+                } = &e
+                {
+                    eprint!("{warning}");
+                    if let Some(disassembly) = disassembly {
+                        println!("{disassembly}");
+                    }
+                }
+                eprintln!("{e}");
+                1
+            },
+        }
+    }
+}
+
+/// Formats the disassembly with all columns aligned, followed by the source
+/// location of each instruction
+fn format_assembly(assembly: &AnnotatedDisassembly) -> String {
+    let mut text = String::new();
+
+    if let Some(header) = assembly.header.as_ref() {
+        text.push_str(header);
+        text.push('\n');
+    }
+    if let Some(reason) = assembly.validation_skipped.as_ref() {
+        text.push_str(&format!("; {reason}\n"));
+    }
+    for error in assembly.validation_errors.iter() {
+        text.push_str(&validation_comment(error));
+    }
+
+    for instruction in assembly.instructions.iter() {
+        let formatted = assembly
+            .lengths
+            .format_instruction(&instruction.disassembly, true);
+        let formatted = formatted.trim_end();
+
+        match instruction.line.as_ref() {
+            Some(line) => text.push_str(&format!("{formatted} ; {}:{}\n", line.file, line.line)),
+            None => text.push_str(&format!("{formatted}\n")),
+        }
+
+        for error in instruction.validation_errors.iter() {
+            text.push_str(&validation_comment(error));
+        }
+    }
+
+    text
+}
+
+/// Formats a validation error as comment lines below the instruction
+fn validation_comment(error: &str) -> String {
+    let mut comment = String::new();
+    for (index, line) in error.lines().enumerate() {
+        let prefix = if index == 0 {
+            "validation error: "
+        } else {
+            "  "
+        };
+        comment.push_str(&format!("; {prefix}{line}\n"));
+    }
+    comment
+}
+
+fn parse_args(args: &[String]) -> Result<CompileCommand> {
+    let mut file = None;
+    let mut shader_kind = None;
+    let mut json = false;
+    let mut options = Map::new();
+    let mut macros = Map::new();
+    let mut include_directories = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| eyre!("Missing value for {name}"))
+        };
+
+        match arg.as_str() {
+            "--kind" => shader_kind = Some(value(arg)?),
+            "--json" => json = true,
+            "-D" => define(&mut macros, &value(arg)?),
+            "-I" => include_directories.push(Value::from(value(arg)?)),
+            // glslc style `-DNAME=VALUE` and `-Idir`
+            define_flag if define_flag.starts_with("-D") => define(&mut macros, &arg[2..]),
+            include_flag if include_flag.starts_with("-I") => {
+                include_directories.push(Value::from(&arg[2..]))
+            },
+            option if option.starts_with("--") => {
+                let raw = value(arg)?;
+                // Decimal numbers are versions like 1.6, which are strings
+                let parsed = match serde_json::from_str::<Value>(&raw) {
+                    Ok(value) if !value.is_f64() => value,
+                    _ => Value::String(raw),
+                };
+                options.insert(camel_case(&option[2..]), parsed);
+            },
+            positional if file.is_none() => file = Some(positional.to_string()),
+            unexpected => bail!("Unexpected argument {unexpected}"),
+        }
+    }
+
+    let file = file.ok_or_else(|| eyre!("No shader file given"))?;
+    let path = Path::new(&file);
+
+    let shader_kind = match shader_kind {
+        Some(shader_kind) => {
+            serde_json::from_value(Value::String(shader_kind)).wrap_err("Invalid shader kind")?
+        },
+        None => path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(ShaderKind::from_extension)
+            .ok_or_else(|| eyre!("Cannot infer the shader kind of {file}, pass --kind"))?,
+    };
+
+    let source =
+        std::fs::read_to_string(path).wrap_err_with(|| format!("Failed to read {file}"))?;
+
+    // Naming the source by its real path lets relative includes resolve next to
+    // it on disk
+    if !options.contains_key("fileName") {
+        let file_name = path
+            .canonicalize()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or(file);
+        options.insert("fileName".to_string(), Value::from(file_name));
+    }
+    if !macros.is_empty() {
+        options.insert("macros".to_string(), Value::Object(macros));
+    }
+    if !include_directories.is_empty() {
+        options.insert(
+            "includeDirectories".to_string(),
+            Value::Array(include_directories),
+        );
+    }
+
+    let options =
+        serde_json::from_value(Value::Object(options)).wrap_err("Invalid compile options")?;
+
+    Ok(CompileCommand {
+        source,
+        shader_kind,
+        options,
+        json,
+    })
+}
+
+/// Adds a `NAME` or `NAME=VALUE` macro definition
+fn define(macros: &mut Map<String, Value>, definition: &str) {
+    match definition.split_once('=') {
+        Some((name, value)) => macros.insert(name.to_string(), Value::from(value)),
+        None => macros.insert(definition.to_string(), Value::Null),
+    };
+}
+
+/// Converts a `--kebab-case` flag name to the `camelCase` option name
+fn camel_case(flag: &str) -> String {
+    let mut parts = flag.split('-');
+    let mut name = parts.next().unwrap_or_default().to_string();
+
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            name.extend(first.to_uppercase());
+            name.push_str(chars.as_str());
+        }
+    }
+
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn parse(args: &[&str]) -> Result<CompileCommand> {
+        // Tests run in parallel, so each one gets its own file
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let file = std::env::temp_dir().join(format!(
+            "shaderc-compile-args-{}-{}.comp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&file, "void main() {}").unwrap();
+
+        let mut arguments = vec![file.to_string_lossy().into_owned()];
+        arguments.extend(args.iter().map(|arg| arg.to_string()));
+        let command = parse_args(&arguments);
+
+        std::fs::remove_file(&file).unwrap();
+        command
+    }
+
+    #[test]
+    fn separate_and_attached_defines() {
+        let command = parse(&["-D", "A", "-DB=2", "-D", "C=x", "-DD"]).unwrap();
+        let macros = &command.options.macros;
+        assert_eq!(macros.get("A"), Some(&None));
+        assert_eq!(macros.get("B"), Some(&Some("2".to_string())));
+        assert_eq!(macros.get("C"), Some(&Some("x".to_string())));
+        assert_eq!(macros.get("D"), Some(&None));
+    }
+
+    #[test]
+    fn separate_and_attached_include_directories() {
+        let command = parse(&["-I", "a", "-Ib"]).unwrap();
+        assert_eq!(command.options.include_directories, ["a", "b"]);
+    }
+
+    #[test]
+    fn kind_is_inferred_from_the_extension() {
+        let command = parse(&["--json"]).unwrap();
+        assert!(command.json);
+        assert!(matches!(command.shader_kind, ShaderKind::Compute));
+    }
+
+    #[test]
+    fn options_are_read_as_json() {
+        let command =
+            parse(&["--spirv-version", "1.6", "--limit-result-name-length", "8"]).unwrap();
+        assert!(matches!(
+            command.options.spirv_version,
+            Some(shaderc_interactive::options::SpirvVersion::V1_6)
+        ));
+        assert_eq!(command.options.limit_result_name_length, Some(8));
+    }
+
+    #[test]
+    fn missing_values_are_errors() {
+        assert!(parse(&["-D"]).is_err());
+        assert!(parse(&["--kind"]).is_err());
+    }
+
+    #[test]
+    fn kebab_case_flags() {
+        assert_eq!(camel_case("target-env"), "targetEnv");
+        assert_eq!(
+            camel_case("limit-result-name-length"),
+            "limitResultNameLength"
+        );
+        assert_eq!(camel_case("invert"), "invert");
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    annotated_disassembly::AnnotatedDisassembly, diagnostics::Diagnostics,
+    options::CompileShaderOptions, CompileError, CompiledShader,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The outcome of a compilation as it is shown in the user interface and
+/// printed by the command line
+#[derive(Serialize, Deserialize)]
+pub enum Compilation {
+    Success {
+        assembly: AnnotatedDisassembly,
+        warning: String,
+        diagnostics: Diagnostics,
+        macros: BTreeMap<String, Option<String>>,
+        target_env_version: String,
+        spirv_version: String,
+    },
+    Failure {
+        error: String,
+        diagnostics: Diagnostics,
+    },
+    /// Compilation succeeded, but the resulting module could not be annotated
+    AnalysisFailure {
+        error: String,
+        /// Plain disassembly, if the module could at least be parsed
+        disassembly: Option<String>,
+        warning: String,
+        diagnostics: Diagnostics,
+    },
+}
+
+impl Compilation {
+    pub fn new(
+        result: Result<CompiledShader, CompileError>,
+        options: &CompileShaderOptions,
+    ) -> Self {
+        match result {
+            Ok(compiled) => {
+                let (major, minor) = compiled.spirv_version;
+
+                Self::Success {
+                    assembly: compiled.assembly,
+                    warning: compiled.warning,
+                    diagnostics: compiled.diagnostics,
+                    macros: options.macros.clone(),
+                    target_env_version: compiled.target_env_version.to_string(),
+                    spirv_version: format!("{major}.{minor}"),
+                }
+            },
+            Err(e) => match &e {
+                CompileError::Analysis {
+                    disassembly,
+                    warning,
+                    diagnostics,
+                    ..
+                } => Self::AnalysisFailure {
+                    error: e.to_string(),
+                    disassembly: disassembly.clone(),
+                    warning: warning.clone(),
+                    diagnostics: diagnostics.clone(),
+                },
+                _ => Self::Failure {
+                    error: e.to_string(),
+                    diagnostics: e.diagnostics(),
+                },
+            },
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Diagnostics {
+    pub messages: Vec<Diagnostic>,
+    pub error_count: u32,
+    pub warning_count: u32,
+}
+
+impl Diagnostics {
+    /// A single error that is not tied to any source location
+    pub fn error(message: &str) -> Self {
+        Self {
+            messages: vec![Diagnostic {
+                severity: Severity::Error,
+                file: None,
+                line: None,
+                column: None,
+                message: message.to_string(),
+            }],
+            error_count: 1,
+            warning_count: 0,
+        }
+    }
+
+    /// Parses shaderc's `file:line: error: message` output.
+    ///
+    /// Lines that do not start a new diagnostic are appended to the previous
+    /// message. The counts are those of the parsed messages, callers should
+    /// replace them with shaderc's own counts where available.
+    pub fn parse(text: &str) -> Self {
+        let mut messages = Vec::<Diagnostic>::new();
+
+        for line in text.lines() {
+            // Summary lines like "1 error generated." carry no information
+            // beyond the counts
+            if line.trim().is_empty() || line.ends_with(" generated.") {
+                continue;
+            }
+
+            if let Some(diagnostic) = parse_line(line) {
+                messages.push(diagnostic);
+            } else if let Some(previous) = messages.last_mut() {
+                previous.message.push('\n');
+                previous.message.push_str(line);
+            }
+        }
+
+        let count = |severity| {
+            messages
+                .iter()
+                .filter(|message| message.severity == severity)
+                .count() as u32
+        };
+
+        Self {
+            error_count: count(Severity::Error),
+            warning_count: count(Severity::Warning),
+            messages,
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<Diagnostic> {
+    const MARKERS: &[(&str, Severity)] = &[
+        ("error: ", Severity::Error),
+        ("warning: ", Severity::Warning),
+    ];
+
+    let (index, marker, severity) = MARKERS
+        .iter()
+        .filter_map(|(marker, severity)| line.find(marker).map(|index| (index, *marker, *severity)))
+        .min_by_key(|(index, _, _)| *index)?;
+
+    let message = line[index + marker.len()..].to_string();
+    let location = &line[..index];
+
+    if location.is_empty() {
+        return Some(Diagnostic {
+            severity,
+            file: None,
+            line: None,
+            column: None,
+            message,
+        });
+    }
+
+    // The location is `file:line:` or `file:line:column:`. File names may
+    // contain colons themselves, e.g. Windows drive letters, so the numbers are
+    // taken from the end
+    let location = location.strip_suffix(": ")?;
+    let mut parts = location.rsplitn(3, ':');
+
+    let last = parts.next()?;
+    let (file, line, column) = match (
+        last.parse::<u32>(),
+        parts.next().map(|part| part.parse::<u32>()),
+    ) {
+        (Ok(column), Some(Ok(line))) => (parts.next()?.to_string(), Some(line), Some(column)),
+        (Ok(line), _) => (location.rsplit_once(':')?.0.to_string(), Some(line), None),
+        (Err(_), _) => (location.to_string(), None, None),
+    };
+
+    Some(Diagnostic {
+        severity,
+        file: Some(file),
+        line,
+        column,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(diagnostic: &Diagnostic) -> (Option<&str>, Option<u32>, Option<u32>) {
+        (
+            diagnostic.file.as_deref(),
+            diagnostic.line,
+            diagnostic.column,
+        )
+    }
+
+    #[test]
+    fn file_line_column() {
+        let diagnostics =
+            Diagnostics::parse("shader.glsl:12:5: error: 'x' : undeclared identifier");
+        let diagnostic = &diagnostics.messages[0];
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(
+            location(diagnostic),
+            (Some("shader.glsl"), Some(12), Some(5))
+        );
+        assert_eq!(diagnostic.message, "'x' : undeclared identifier");
+    }
+
+    #[test]
+    fn file_line() {
+        let diagnostics = Diagnostics::parse("shader.glsl:3: warning: attribute deprecated");
+        let diagnostic = &diagnostics.messages[0];
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(location(diagnostic), (Some("shader.glsl"), Some(3), None));
+        assert_eq!(diagnostic.message, "attribute deprecated");
+    }
+
+    #[test]
+    fn without_location() {
+        let diagnostics = Diagnostics::parse("error: Linking compute stage: Missing entry point");
+        let diagnostic = &diagnostics.messages[0];
+        assert_eq!(location(diagnostic), (None, None, None));
+        assert_eq!(
+            diagnostic.message,
+            "Linking compute stage: Missing entry point"
+        );
+    }
+
+    #[test]
+    fn file_without_line() {
+        let diagnostics = Diagnostics::parse("shader.glsl: error: #version: bad profile");
+        let diagnostic = &diagnostics.messages[0];
+        assert_eq!(location(diagnostic), (Some("shader.glsl"), None, None));
+        assert_eq!(diagnostic.message, "#version: bad profile");
+    }
+
+    #[test]
+    fn windows_include_paths() {
+        let diagnostics = Diagnostics::parse(
+            "C:\\shaders\\common.glsl:7:2: error: syntax error\n\
+             D:\\lib.glsl:40: warning: unused",
+        );
+        assert_eq!(
+            location(&diagnostics.messages[0]),
+            (Some("C:\\shaders\\common.glsl"), Some(7), Some(2))
+        );
+        assert_eq!(
+            location(&diagnostics.messages[1]),
+            (Some("D:\\lib.glsl"), Some(40), None)
+        );
+    }
+
+    #[test]
+    fn continuation_lines_and_counts() {
+        let diagnostics = Diagnostics::parse(
+            "a.glsl:1: warning: first\n\
+             a.glsl:2: error: second\n\
+             \x20 more about the second\n\
+             \n\
+             b.glsl:3:4: error: third\n\
+             2 errors generated.\n",
+        );
+        assert_eq!(diagnostics.messages.len(), 3);
+        assert_eq!(
+            diagnostics.messages[1].message,
+            "second\n  more about the second"
+        );
+        assert_eq!(diagnostics.error_count, 2);
+        assert_eq!(diagnostics.warning_count, 1);
+    }
+
+    #[test]
+    fn earliest_marker_wins() {
+        let diagnostics = Diagnostics::parse("a.glsl:1: warning: turns into error: later");
+        let diagnostic = &diagnostics.messages[0];
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(diagnostic.message, "turns into error: later");
+    }
+
+    #[test]
+    fn single_error() {
+        let diagnostics = Diagnostics::error("failed");
+        assert_eq!(diagnostics.error_count, 1);
+        assert_eq!(diagnostics.warning_count, 0);
+        assert_eq!(location(&diagnostics.messages[0]), (None, None, None));
+    }
+}
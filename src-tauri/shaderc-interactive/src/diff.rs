@@ -0,0 +1,673 @@
+use crate::{
+    annotated_disassembly::AnnotatedDisassembly,
+    compile_shader,
+    module_info::ModuleInfo,
+    options::{CompileShaderOptions, ShaderKind},
+    CompileError, CompiledShader,
+};
+use itertools::Itertools;
+use rspirv::dr::{Instruction, Module, Operand};
+use serde::{Deserialize, Serialize};
+use spirv::Word;
+use std::collections::HashMap;
+
+/// A shader and the options to compile it with
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompileRequest {
+    pub source: String,
+    pub shader_kind: ShaderKind,
+    #[serde(default)]
+    pub options: CompileShaderOptions,
+}
+
+/// One line of an instruction alignment. Positions refer to
+/// `AnnotatedDisassembly::instructions` of the left and right module
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstructionDiff {
+    Equal { left: usize, right: usize },
+    Modify { left: usize, right: usize },
+    Delete { left: usize },
+    Insert { right: usize },
+}
+
+pub struct ShaderDiff {
+    pub left: Result<CompiledShader, CompileError>,
+    pub right: Result<CompiledShader, CompileError>,
+    /// Empty unless both shaders compiled
+    pub instructions: Vec<InstructionDiff>,
+}
+
+/// Compiles two shaders and aligns their instructions
+pub fn diff_shaders(left: &CompileRequest, right: &CompileRequest) -> ShaderDiff {
+    let compile = |request: &CompileRequest| {
+        compile_shader(&request.source, request.shader_kind, &request.options)
+    };
+    let left = compile(left);
+    let right = compile(right);
+
+    let instructions = match (&left, &right) {
+        (Ok(left), Ok(right)) => {
+            diff_instructions(&left.module, &left.assembly, &right.module, &right.assembly)
+        },
+        _ => Vec::new(),
+    };
+
+    ShaderDiff {
+        left,
+        right,
+        instructions,
+    }
+}
+
+/// Aligns the shown instructions of two modules.
+///
+/// Ids are compared by their friendly names, so that two compilations line up
+/// even if their ids are numbered differently. Ids without a name are first
+/// treated as interchangeable. Once aligned, the results of matching
+/// instructions tell which unnamed ids correspond to each other, and matches
+/// that use different ones are reported as modified.
+pub fn diff_instructions(
+    left_module: &Module,
+    left_assembly: &AnnotatedDisassembly,
+    right_module: &Module,
+    right_assembly: &AnnotatedDisassembly,
+) -> Vec<InstructionDiff> {
+    let left = left_assembly.shown_instructions(left_module);
+    let right = right_assembly.shown_instructions(right_module);
+
+    // Intern the keys, so that the alignment only compares integers
+    let mut interned = HashMap::new();
+    let mut intern = |instructions: &[&Instruction], info: &ModuleInfo| {
+        instructions
+            .iter()
+            .map(|instruction| {
+                let key = alignment_key(instruction, info);
+                let next = interned.len();
+                *interned.entry(key).or_insert(next)
+            })
+            .collect_vec()
+    };
+    let left_keys = intern(&left, &left_assembly.info);
+    let right_keys = intern(&right, &right_assembly.info);
+
+    let mut diff = pair_modifications(shortest_edit(&left_keys, &right_keys), &left, &right);
+
+    // Unnamed ids correspond if they are the results of aligned instructions
+    let mut ids = HashMap::<Word, Word>::new();
+    for line in diff.iter() {
+        if let InstructionDiff::Equal { left: l, right: r }
+        | InstructionDiff::Modify { left: l, right: r } = *line
+        {
+            if let (Some(left_id), Some(right_id)) = (left[l].result_id, right[r].result_id) {
+                ids.insert(left_id, right_id);
+            }
+        }
+    }
+
+    for line in diff.iter_mut() {
+        if let InstructionDiff::Equal { left: l, right: r } = *line {
+            if !same_ids(left[l], right[r], &ids) {
+                *line = InstructionDiff::Modify { left: l, right: r };
+            }
+        }
+    }
+
+    diff
+}
+
+/// The instruction with every id replaced by its friendly name. Unnamed ids are
+/// all rendered the same, and the id suffixes that `ModuleInfo` appends to
+/// colliding names are dropped
+fn alignment_key(instruction: &Instruction, info: &ModuleInfo) -> String {
+    let id_name = |id: Word| -> String {
+        match info.names.get(&id) {
+            Some(name) => {
+                // Taken names get the suffix again, e.g. `x_5_5`
+                let suffix = format!("_{id}");
+                let mut name = name.as_str();
+                while let Some(base) = name.strip_suffix(&suffix) {
+                    name = base;
+                }
+                format!("%{name}")
+            },
+            None => "%_".to_string(),
+        }
+    };
+
+    let mut key = format!("Op{}", instruction.class.opname);
+    for id in instruction
+        .result_type
+        .iter()
+        .chain(instruction.result_id.iter())
+    {
+        key.push(' ');
+        key.push_str(&id_name(*id));
+    }
+    for operand in instruction.operands.iter() {
+        key.push(' ');
+        match operand {
+            Operand::IdRef(id) => key.push_str(&id_name(*id)),
+            _ => key.push_str(&operand.to_string()),
+        }
+    }
+    key
+}
+
+/// Whether two instructions with the same alignment key refer to corresponding
+/// ids. Ids that were not aligned, e.g. forward references to deleted
+/// instructions, are only checked by name
+fn same_ids(left: &Instruction, right: &Instruction, ids: &HashMap<Word, Word>) -> bool {
+    let same = |left: Word, right: Word| ids.get(&left).map_or(true, |&id| id == right);
+
+    let left_ids = left
+        .result_type
+        .iter()
+        .chain(left.operands.iter().filter_map(|operand| match operand {
+            Operand::IdRef(id) => Some(id),
+            _ => None,
+        }));
+    let right_ids = right
+        .result_type
+        .iter()
+        .chain(right.operands.iter().filter_map(|operand| match operand {
+            Operand::IdRef(id) => Some(id),
+            _ => None,
+        }));
+
+    left_ids.zip(right_ids).all(|(&l, &r)| same(l, r))
+}
+
+/// Myers' shortest edit script between two key sequences, as a sequence of
+/// `Equal`, `Delete` and `Insert`.
+///
+/// This is the linear space variant, which splits both sequences at the middle
+/// snake of an optimal path instead of keeping every round for backtracking.
+fn shortest_edit(left: &[usize], right: &[usize]) -> Vec<InstructionDiff> {
+    let mut edits = Vec::with_capacity(left.len().max(right.len()));
+    edit_range(left, right, (0, 0), &mut edits);
+    edits
+}
+
+/// Appends the shortest edit script of two subsequences, which start at
+/// `origin` in the full sequences
+fn edit_range(
+    left: &[usize],
+    right: &[usize],
+    origin: (usize, usize),
+    edits: &mut Vec<InstructionDiff>,
+) {
+    let equal = |edits: &mut Vec<_>, from: (usize, usize), count: usize| {
+        edits.extend((0..count).map(|i| InstructionDiff::Equal {
+            left: origin.0 + from.0 + i,
+            right: origin.1 + from.1 + i,
+        }));
+    };
+
+    // Common ends need no search
+    let prefix = common_length(left.iter(), right.iter());
+    let suffix = common_length(left[prefix..].iter().rev(), right[prefix..].iter().rev());
+    let (n, m) = (left.len() - suffix, right.len() - suffix);
+    equal(edits, (0, 0), prefix);
+
+    if prefix == n {
+        edits.extend((prefix..m).map(|r| InstructionDiff::Insert {
+            right: origin.1 + r,
+        }));
+    } else if prefix == m {
+        edits.extend((prefix..n).map(|l| InstructionDiff::Delete { left: origin.0 + l }));
+    } else {
+        // Both sides differ in their first and last key, so there are at least
+        // two edits and each half of the path has fewer than the whole
+        let (middle_left, middle_right) = (&left[prefix..n], &right[prefix..m]);
+        let (start, end) = middle_snake(middle_left, middle_right);
+
+        let inner = (origin.0 + prefix, origin.1 + prefix);
+        edit_range(
+            &middle_left[..start.0],
+            &middle_right[..start.1],
+            inner,
+            edits,
+        );
+        equal(edits, (prefix + start.0, prefix + start.1), end.0 - start.0);
+        edit_range(
+            &middle_left[end.0..],
+            &middle_right[end.1..],
+            (inner.0 + end.0, inner.1 + end.1),
+            edits,
+        );
+    }
+
+    equal(edits, (n, m), suffix);
+}
+
+fn common_length<'a>(
+    left: impl Iterator<Item = &'a usize>,
+    right: impl Iterator<Item = &'a usize>,
+) -> usize {
+    left.zip(right).take_while(|(l, r)| l == r).count()
+}
+
+/// The start and end of the snake in the middle of a shortest edit path,
+/// found by searching from both corners until the paths overlap
+fn middle_snake(left: &[usize], right: &[usize]) -> ((usize, usize), (usize, usize)) {
+    let (n, m) = (left.len() as isize, right.len() as isize);
+    let delta = n - m;
+    let odd = delta % 2 != 0;
+    let max = (n + m + 1) / 2;
+    let offset = max + 1;
+
+    // The furthest x reached on every diagonal k = x - y, counted from the
+    // start for the forward search and from the end for the backward one
+    let mut forward = vec![0isize; 2 * max as usize + 3];
+    let mut backward = vec![0isize; 2 * max as usize + 3];
+    let index = |k: isize| (k + offset) as usize;
+
+    for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let mut x = furthest(&forward, k, d, &index);
+            let mut y = x - k;
+            let (start_x, start_y) = (x, y);
+            while x < n && y < m && left[x as usize] == right[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[index(k)] = x;
+
+            // The backward search has finished round d - 1
+            let backward_k = delta - k;
+            if odd
+                && (-(d - 1)..=d - 1).contains(&backward_k)
+                && x + backward[index(backward_k)] >= n
+            {
+                let start = (start_x as usize, start_y as usize);
+                return (start, (x as usize, y as usize));
+            }
+        }
+
+        for k in (-d..=d).step_by(2) {
+            let mut x = furthest(&backward, k, d, &index);
+            let mut y = x - k;
+            let (start_x, start_y) = (x, y);
+            while x < n && y < m && left[(n - x - 1) as usize] == right[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+            backward[index(k)] = x;
+
+            let forward_k = delta - k;
+            if !odd && (-d..=d).contains(&forward_k) && x + forward[index(forward_k)] >= n {
+                let start = ((n - x) as usize, (m - y) as usize);
+                return (start, ((n - start_x) as usize, (m - start_y) as usize));
+            }
+        }
+    }
+
+    unreachable!("Paths of length n + m always overlap")
+}
+
+/// Where the path on diagonal k continues in round d, one insertion or deletion
+/// after the furthest point of a neighbouring diagonal
+fn furthest(v: &[isize], k: isize, d: isize, index: &dyn Fn(isize) -> usize) -> isize {
+    if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+        v[index(k + 1)]
+    } else {
+        v[index(k - 1)] + 1
+    }
+}
+
+/// Turns deletions followed by insertions of the same opcode into
+/// modifications, keeping the order of both sides
+fn pair_modifications(
+    edits: Vec<InstructionDiff>,
+    left: &[&Instruction],
+    right: &[&Instruction],
+) -> Vec<InstructionDiff> {
+    let mut diff = Vec::with_capacity(edits.len());
+
+    let mut deleted = Vec::new();
+    let mut inserted = Vec::new();
+    let flush = |deleted: &mut Vec<usize>, inserted: &mut Vec<usize>, diff: &mut Vec<_>| {
+        let mut next_insert = 0;
+        for &l in deleted.iter() {
+            let opcode = left[l].class.opcode;
+            let matching = inserted[next_insert..]
+                .iter()
+                .position(|&r| right[r].class.opcode == opcode);
+
+            match matching {
+                Some(skip) => {
+                    for &r in inserted[next_insert..next_insert + skip].iter() {
+                        diff.push(InstructionDiff::Insert { right: r });
+                    }
+                    let r = inserted[next_insert + skip];
+                    diff.push(InstructionDiff::Modify { left: l, right: r });
+                    next_insert += skip + 1;
+                },
+                None => diff.push(InstructionDiff::Delete { left: l }),
+            }
+        }
+        for &r in inserted[next_insert..].iter() {
+            diff.push(InstructionDiff::Insert { right: r });
+        }
+
+        deleted.clear();
+        inserted.clear();
+    };
+
+    for edit in edits {
+        match edit {
+            InstructionDiff::Delete { left } => deleted.push(left),
+            InstructionDiff::Insert { right } => inserted.push(right),
+            _ => {
+                flush(&mut deleted, &mut inserted, &mut diff);
+                diff.push(edit);
+            },
+        }
+    }
+    flush(&mut deleted, &mut inserted, &mut diff);
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assembler::assemble, diagnostics::Diagnostics};
+    use spirv::Op;
+    use InstructionDiff::{Delete, Equal, Insert, Modify};
+
+    /// Checks that the script walks both sides in order and only matches equal
+    /// keys, returning the number of edits
+    fn edit_distance(left: &[usize], right: &[usize], script: &[InstructionDiff]) -> usize {
+        let (mut l, mut r) = (0, 0);
+        let mut distance = 0;
+        for edit in script {
+            match *edit {
+                Equal {
+                    left: el,
+                    right: er,
+                } => {
+                    assert_eq!((el, er), (l, r));
+                    assert_eq!(left[el], right[er]);
+                    l += 1;
+                    r += 1;
+                },
+                Delete { left: el } => {
+                    assert_eq!(el, l);
+                    l += 1;
+                    distance += 1;
+                },
+                Insert { right: er } => {
+                    assert_eq!(er, r);
+                    r += 1;
+                    distance += 1;
+                },
+                Modify { .. } => panic!("shortest_edit does not modify"),
+            }
+        }
+        assert_eq!((l, r), (left.len(), right.len()));
+        distance
+    }
+
+    #[test]
+    fn empty_sides() {
+        assert_eq!(shortest_edit(&[], &[]), []);
+        assert_eq!(
+            shortest_edit(&[], &[1, 2]),
+            [Insert { right: 0 }, Insert { right: 1 }]
+        );
+        assert_eq!(
+            shortest_edit(&[1, 2], &[]),
+            [Delete { left: 0 }, Delete { left: 1 }]
+        );
+    }
+
+    #[test]
+    fn identical_sides() {
+        assert_eq!(
+            shortest_edit(&[3, 1, 3], &[3, 1, 3]),
+            [
+                Equal { left: 0, right: 0 },
+                Equal { left: 1, right: 1 },
+                Equal { left: 2, right: 2 }
+            ]
+        );
+    }
+
+    #[test]
+    fn pure_insertion_and_deletion() {
+        assert_eq!(
+            shortest_edit(&[1, 2, 3], &[1, 4, 2, 3, 5]),
+            [
+                Equal { left: 0, right: 0 },
+                Insert { right: 1 },
+                Equal { left: 1, right: 2 },
+                Equal { left: 2, right: 3 },
+                Insert { right: 4 },
+            ]
+        );
+        assert_eq!(
+            shortest_edit(&[1, 4, 2, 3, 5], &[1, 2, 3]),
+            [
+                Equal { left: 0, right: 0 },
+                Delete { left: 1 },
+                Equal { left: 2, right: 1 },
+                Equal { left: 3, right: 2 },
+                Delete { left: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn scripts_are_shortest() {
+        // The classic example from Myers' paper has a distance of 5
+        let (a, b, c) = (0, 1, 2);
+        let left = [a, b, c, a, b, b, a];
+        let right = [c, b, a, b, a, c];
+        assert_eq!(
+            edit_distance(&left, &right, &shortest_edit(&left, &right)),
+            5
+        );
+
+        let left = [1, 2, 3, 4, 5, 6, 7, 8];
+        let right = [8, 1, 3, 4, 9, 6, 7, 2];
+        assert_eq!(
+            edit_distance(&left, &right, &shortest_edit(&left, &right)),
+            6
+        );
+    }
+
+    /// A small linear congruential generator, so the random tests are
+    /// reproducible
+    fn random_keys(seed: &mut u64, len: usize, alphabet: u64) -> Vec<usize> {
+        (0..len)
+            .map(|_| {
+                *seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((*seed >> 33) % alphabet) as usize
+            })
+            .collect()
+    }
+
+    /// The edit distance by dynamic programming over the longest common
+    /// subsequence
+    fn quadratic_edit_distance(left: &[usize], right: &[usize]) -> usize {
+        let mut lcs = vec![vec![0; right.len() + 1]; left.len() + 1];
+        for (l, left_key) in left.iter().enumerate() {
+            for (r, right_key) in right.iter().enumerate() {
+                lcs[l + 1][r + 1] = if left_key == right_key {
+                    lcs[l][r] + 1
+                } else {
+                    lcs[l][r + 1].max(lcs[l + 1][r])
+                };
+            }
+        }
+        left.len() + right.len() - 2 * lcs[left.len()][right.len()]
+    }
+
+    #[test]
+    fn random_scripts_are_shortest() {
+        let mut seed = 1;
+        for round in 0..500 {
+            let left = random_keys(&mut seed, round % 37, 1 + round as u64 % 5);
+            let right = random_keys(&mut seed, round % 41, 1 + round as u64 % 5);
+            assert_eq!(
+                edit_distance(&left, &right, &shortest_edit(&left, &right)),
+                quadratic_edit_distance(&left, &right),
+                "{left:?} {right:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn large_inputs_with_many_edits() {
+        // Keeping every round for backtracking would take d² words, tens of
+        // millions here
+        let mut seed = 7;
+        let left = random_keys(&mut seed, 100_000, 1_000_000);
+        let mut right = left.clone();
+        let positions = random_keys(&mut seed, 5_000, 95_000);
+        for (i, &position) in positions.iter().enumerate() {
+            if i % 2 == 0 {
+                right[position] = 1_000_000 + i;
+            } else {
+                right.insert(position, 1_000_000 + i);
+            }
+        }
+
+        let distance = edit_distance(&left, &right, &shortest_edit(&left, &right));
+        // Each replacement takes two edits and each insertion one
+        assert!(distance <= 7_500, "{distance}");
+    }
+
+    #[test]
+    fn deletions_and_insertions_of_one_opcode_pair_up() {
+        let instruction = |opcode| Instruction::new(opcode, None, None, Vec::new());
+        let left = [
+            instruction(Op::Nop),
+            instruction(Op::Return),
+            instruction(Op::Kill),
+        ];
+        let right = [
+            instruction(Op::Unreachable),
+            instruction(Op::Return),
+            instruction(Op::Nop),
+        ];
+        let left = left.iter().collect_vec();
+        let right = right.iter().collect_vec();
+
+        // Pairing the Nops passes the inserted Return, so the deleted one cannot
+        // pair up without reordering. The Kill has no counterpart at all
+        assert_eq!(
+            pair_modifications(
+                vec![
+                    Delete { left: 0 },
+                    Delete { left: 1 },
+                    Delete { left: 2 },
+                    Insert { right: 0 },
+                    Insert { right: 1 },
+                    Insert { right: 2 },
+                ],
+                &left,
+                &right
+            ),
+            [
+                Insert { right: 0 },
+                Insert { right: 1 },
+                Modify { left: 0, right: 2 },
+                Delete { left: 1 },
+                Delete { left: 2 },
+            ]
+        );
+    }
+
+    fn diff(left: &str, right: &str) -> Vec<InstructionDiff> {
+        let annotated = |text: &str| {
+            let module = assemble(text).unwrap();
+            let assembly =
+                crate::annotate_module(&module, None, "", &Diagnostics::default()).unwrap();
+            (module, assembly)
+        };
+        let (left, left_assembly) = annotated(left);
+        let (right, right_assembly) = annotated(right);
+        diff_instructions(&left, &left_assembly, &right, &right_assembly)
+    }
+
+    /// Two variables with the same name, so `ModuleInfo` suffixes them with
+    /// their ids
+    fn shared_names(a: u32, b: u32, first: u32, second: u32) -> String {
+        format!(
+            "OpCapability Shader
+             OpMemoryModel Logical GLSL450
+             OpName %{a} \"x\"
+             OpName %{b} \"x\"
+        %1 = OpTypeInt 32 1
+        %2 = OpTypePointer Private %1
+        %3 = OpTypeVoid
+        %4 = OpTypeFunction %3
+       %{a} = OpVariable %2 Private
+       %{b} = OpVariable %2 Private
+       %20 = OpFunction %3 None %4
+       %21 = OpLabel
+       %22 = OpLoad %1 %{first}
+       %23 = OpLoad %1 %{second}
+             OpReturn
+             OpFunctionEnd"
+        )
+    }
+
+    #[test]
+    fn renumbered_names_are_equal() {
+        let diff = diff(&shared_names(5, 6, 5, 6), &shared_names(7, 8, 7, 8));
+        assert!(diff.iter().all(|line| matches!(line, Equal { .. })));
+    }
+
+    #[test]
+    fn swapped_ids_with_the_same_name_are_modified() {
+        // Every key is the same, only the ids the loads refer to differ
+        let diff = diff(&shared_names(5, 6, 5, 6), &shared_names(7, 8, 8, 7));
+        let modified = diff
+            .iter()
+            .filter_map(|line| match *line {
+                Modify { left, right } => Some((left, right)),
+                _ => None,
+            })
+            .collect_vec();
+
+        assert_eq!(diff.len(), 14);
+        assert_eq!(modified, [(10, 10), (11, 11)]);
+    }
+
+    #[test]
+    fn names_suffixed_twice_are_equal() {
+        // The first x takes the name x_5 of the third variable, so it becomes
+        // x_5_5 on the left but only x_7 on the right
+        let module = |a: u32, b: u32, c: u32| {
+            format!(
+                "OpCapability Shader
+                 OpMemoryModel Logical GLSL450
+                 OpName %{a} \"x\"
+                 OpName %{b} \"x\"
+                 OpName %{c} \"x_5\"
+            %1 = OpTypeInt 32 1
+            %2 = OpTypePointer Private %1
+            %3 = OpTypeVoid
+            %4 = OpTypeFunction %3
+           %{a} = OpVariable %2 Private
+           %{b} = OpVariable %2 Private
+           %{c} = OpVariable %2 Private
+           %20 = OpFunction %3 None %4
+           %21 = OpLabel
+           %22 = OpLoad %1 %{a}
+           %23 = OpLoad %1 %{c}
+                 OpReturn
+                 OpFunctionEnd"
+            )
+        };
+
+        let diff = diff(&module(5, 6, 8), &module(7, 9, 10));
+        assert!(diff.iter().all(|line| matches!(line, Equal { .. })));
+    }
+}
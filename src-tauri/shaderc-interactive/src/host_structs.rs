@@ -0,0 +1,1149 @@
+use crate::{
+    layout::{MatrixLayout, StructLayout},
+    module_info::ModuleInfo,
+    operands::InstructionOperands,
+    reflection::{Decorations, Definitions},
+};
+use eyre::{bail, eyre, Result};
+use itertools::Itertools;
+use rspirv::dr::{Module, Operand};
+use serde::{Deserialize, Serialize};
+use spirv::{Decoration, Op, Word};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::Write,
+};
+
+/// The language to generate host-side struct definitions in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostLanguage {
+    /// `#[repr(C)]` structs deriving `bytemuck::Pod`, checked with
+    /// `assert!(offset_of!(...))`, which needs Rust 1.77
+    Rust,
+    /// Structs checked with `static_assert(offsetof(...))`
+    Cpp,
+}
+
+/// Generates a host-side definition of every uniform, storage and push
+/// constant block and of the structs they contain.
+///
+/// Members are placed at their offsets in the shader by explicit padding
+/// fields. Arrays whose stride exceeds the element size get a wrapper struct
+/// that pads every element, and matrices are written as arrays of vectors
+/// padded to the matrix stride. Half floats are stored as their bits.
+pub fn host_structs(module: &Module, info: &ModuleInfo, language: HostLanguage) -> Result<String> {
+    let decorations = Decorations::collect(module)?;
+    let mut generator = Generator {
+        language,
+        definitions: Definitions::new(module, &info.names),
+        layouts: info
+            .structs
+            .iter()
+            .map(|layout| (layout.id, layout))
+            .collect(),
+        decorations,
+        names: HashMap::new(),
+        used_names: HashSet::new(),
+        alignments: HashMap::new(),
+        unsupported_lengths: BTreeSet::new(),
+        output: String::new(),
+    };
+
+    let blocks = info
+        .structs
+        .iter()
+        .filter(|layout| {
+            generator.decorations.has(layout.id, Decoration::Block)
+                || generator
+                    .decorations
+                    .has(layout.id, Decoration::BufferBlock)
+        })
+        .filter(|layout| layout.members.iter().all(|member| member.offset.is_some()))
+        .map(|layout| layout.id)
+        .collect::<Vec<_>>();
+
+    let mut needed = HashSet::new();
+    for block in blocks {
+        generator.collect_structs(block, &mut needed)?;
+    }
+
+    generator.output.push_str(match language {
+        HostLanguage::Rust => "use bytemuck::{Pod, Zeroable};\n",
+        HostLanguage::Cpp => "#include <cstddef>\n#include <cstdint>\n",
+    });
+    if needed.is_empty() {
+        generator
+            .output
+            .push_str("\n// The module has no uniform, storage or push constant blocks\n");
+    }
+
+    // Types are declared before they are used, so nested structs come first
+    for layout in info.structs.iter() {
+        if needed.contains(&layout.id) {
+            generator.write_struct(layout)?;
+        }
+    }
+
+    if !generator.unsupported_lengths.is_empty() {
+        let lengths = generator.unsupported_lengths.iter().join(", ");
+        let note = format!(
+            "\n// Arrays of length {lengths} implement Pod only with the \
+             `min_const_generics` feature of bytemuck\n"
+        );
+        let header_end = generator.output.find('\n').map_or(0, |end| end + 1);
+        generator.output.insert_str(header_end, &note);
+    }
+
+    Ok(generator.output)
+}
+
+/// A member type on the host, as a base type with array dimensions, outermost
+/// first
+struct HostType {
+    base: String,
+    dimensions: Vec<u32>,
+    align: u32,
+    size: u32,
+    comment: Option<String>,
+}
+
+impl HostType {
+    fn scalar(base: &str, size: u32) -> Self {
+        Self {
+            base: base.to_string(),
+            dimensions: Vec::new(),
+            align: size,
+            size,
+            comment: None,
+        }
+    }
+
+    fn array(mut self, length: u32) -> Self {
+        self.dimensions.insert(0, length);
+        self.size *= length;
+        self
+    }
+}
+
+/// A field of a generated struct
+struct Field {
+    name: String,
+    ty: HostType,
+    offset: u32,
+    /// Explicit padding is not checked by offset
+    padding: bool,
+}
+
+struct Generator<'a, 'm> {
+    language: HostLanguage,
+    definitions: Definitions<'m>,
+    layouts: HashMap<Word, &'a StructLayout>,
+    decorations: Decorations,
+    /// Host names of the generated structs
+    names: HashMap<Word, String>,
+    used_names: HashSet<String>,
+    alignments: HashMap<Word, u32>,
+    /// Array lengths that bytemuck only supports with `min_const_generics`
+    unsupported_lengths: BTreeSet<u32>,
+    output: String,
+}
+
+impl Generator<'_, '_> {
+    /// Adds a struct and every struct its members contain
+    fn collect_structs(&self, id: Word, needed: &mut HashSet<Word>) -> Result<()> {
+        if !needed.insert(id) {
+            return Ok(());
+        }
+
+        let definition = self
+            .definitions
+            .get(id)
+            .ok_or_else(|| eyre!("Struct {id} is not defined"))?;
+        for index in 0..definition.operands.len() {
+            let mut member_type = definition.id_ref(index)?;
+            while let Some(array) = self.definitions.get(member_type) {
+                match array.class.opcode {
+                    Op::TypeArray | Op::TypeRuntimeArray => member_type = array.id_ref(0)?,
+                    Op::TypeStruct => {
+                        self.collect_structs(member_type, needed)?;
+                        break;
+                    },
+                    _ => break,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_struct(&mut self, layout: &StructLayout) -> Result<()> {
+        let name = self.unique_name(&layout.name);
+        let definition = self
+            .definitions
+            .get(layout.id)
+            .ok_or_else(|| eyre!("Struct {} is not defined", layout.name))?;
+
+        let mut members = layout.members.iter().collect::<Vec<_>>();
+        members.sort_by_key(|member| member.offset);
+
+        let mut fields = Vec::new();
+        let mut trailing = None;
+        let mut end = 0;
+        let mut align = 1;
+        let mut packed = false;
+
+        for (position, member) in members.iter().enumerate() {
+            let member_name = field_name(member.name.as_deref(), member.index);
+            let offset = member
+                .offset
+                .ok_or_else(|| eyre!("Member {member_name} of {} has no offset", layout.name))?;
+            let member_type = definition.id_ref(member.index as usize)?;
+            let wrapper = format!("{name}{}", upper_first(&member_name));
+
+            if offset < end {
+                bail!(
+                    "Member {member_name} of {} at {offset} overlaps the previous member",
+                    layout.name
+                );
+            }
+            if offset > end {
+                fields.extend(self.padding(&fields, end, offset - end));
+            }
+
+            if let Some(array) = self.definitions.get_op(member_type, Op::TypeRuntimeArray) {
+                if position + 1 != members.len() {
+                    bail!(
+                        "The runtime array {member_name} is not the last member of {}",
+                        layout.name
+                    );
+                }
+
+                let stride = self.array_stride(member_type)?;
+                let element = self.element_type(
+                    array.id_ref(0)?,
+                    stride,
+                    (layout.id, member.index),
+                    &wrapper,
+                )?;
+                trailing = Some(format!(
+                    "Followed by {member_name}, a runtime array of {} at offset {offset}",
+                    self.spelling(&element),
+                ));
+                end = offset;
+                break;
+            }
+
+            let ty = self.host_type(member_type, (layout.id, member.index), &wrapper)?;
+            if offset % ty.align != 0 {
+                packed = true;
+            }
+            align = align.max(ty.align);
+            end = offset + ty.size;
+            fields.push(Field {
+                name: member_name,
+                ty,
+                offset,
+                padding: false,
+            });
+        }
+
+        if packed {
+            align = 1;
+        }
+        // Trailing padding of the shader's layout is kept, so that arrays and
+        // nested structs line up
+        let size = round_up(end, align).max(layout.size.unwrap_or_default());
+        if size > end {
+            fields.extend(self.padding(&fields, end, size - end));
+        }
+
+        self.names.insert(layout.id, name.clone());
+        self.alignments.insert(layout.id, align);
+        self.write_definition(&name, &fields, size, packed, trailing.as_deref());
+
+        Ok(())
+    }
+
+    /// Explicit padding, numbered after the padding fields before it.
+    ///
+    /// bytemuck only implements `Pod` for arrays of some lengths unless its
+    /// `min_const_generics` feature is enabled, so Rust padding is split into
+    /// arrays of those lengths.
+    fn padding(&self, fields: &[Field], offset: u32, size: u32) -> Vec<Field> {
+        let first = fields.iter().filter(|field| field.padding).count();
+
+        let mut chunks = Vec::new();
+        let mut remaining = size;
+        while remaining > 0 {
+            let chunk = match self.language {
+                HostLanguage::Rust => POD_ARRAY_LENGTHS
+                    .iter()
+                    .copied()
+                    .find(|&length| length <= remaining)
+                    .unwrap_or(remaining),
+                HostLanguage::Cpp => remaining,
+            };
+            chunks.push(chunk);
+            remaining -= chunk;
+        }
+
+        let mut offset = offset;
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                let field = Field {
+                    name: format!("_pad{}", first + index),
+                    ty: self.scalar_type(Op::TypeInt, 8, false).array(chunk),
+                    offset,
+                    padding: true,
+                };
+                offset += chunk;
+                field
+            })
+            .collect()
+    }
+
+    fn write_definition(
+        &mut self,
+        name: &str,
+        fields: &[Field],
+        size: u32,
+        packed: bool,
+        trailing: Option<&str>,
+    ) {
+        let mut text = String::new();
+
+        match self.language {
+            HostLanguage::Rust => {
+                let repr = if packed { "C, packed" } else { "C" };
+                let _ = writeln!(text, "\n#[repr({repr})]");
+                let _ = writeln!(text, "#[derive(Clone, Copy, Pod, Zeroable)]");
+                let _ = writeln!(text, "pub struct {name} {{");
+                for field in fields {
+                    if let Some(comment) = field.ty.comment.as_ref() {
+                        let _ = writeln!(text, "    // {comment}");
+                    }
+                    let _ = writeln!(
+                        text,
+                        "    pub {}: {},",
+                        field.name,
+                        self.spelling(&field.ty)
+                    );
+                }
+                if let Some(trailing) = trailing {
+                    let _ = writeln!(text, "    // {trailing}");
+                }
+                let _ = writeln!(text, "}}");
+                let _ = writeln!(
+                    text,
+                    "const _: () = assert!(std::mem::size_of::<{name}>() == {size});"
+                );
+                for field in fields.iter().filter(|field| !field.padding) {
+                    let _ = writeln!(
+                        text,
+                        "const _: () = assert!(std::mem::offset_of!({name}, {}) == {});",
+                        field.name, field.offset,
+                    );
+                }
+
+                self.unsupported_lengths.extend(
+                    fields
+                        .iter()
+                        .flat_map(|field| field.ty.dimensions.iter().copied())
+                        .filter(|length| *length > 32 && !POD_ARRAY_LENGTHS.contains(length)),
+                );
+            },
+            HostLanguage::Cpp => {
+                if packed {
+                    let _ = writeln!(text, "\n#pragma pack(push, 1)");
+                } else {
+                    text.push('\n');
+                }
+                let _ = writeln!(text, "struct {name} {{");
+                for field in fields {
+                    if let Some(comment) = field.ty.comment.as_ref() {
+                        let _ = writeln!(text, "    // {comment}");
+                    }
+                    let _ = writeln!(text, "    {};", cpp_declaration(&field.ty, &field.name));
+                }
+                if let Some(trailing) = trailing {
+                    let _ = writeln!(text, "    // {trailing}");
+                }
+                let _ = writeln!(text, "}};");
+                if packed {
+                    let _ = writeln!(text, "#pragma pack(pop)");
+                }
+
+                // An empty C++ struct still occupies a byte
+                if size > 0 {
+                    let _ = writeln!(
+                        text,
+                        "static_assert(sizeof({name}) == {size}, \"{name} must be {size} bytes\");"
+                    );
+                }
+                for field in fields.iter().filter(|field| !field.padding) {
+                    let _ = writeln!(
+                        text,
+                        "static_assert(offsetof({name}, {field}) == {offset}, \"{name}::{field} \
+                         must be at offset {offset}\");",
+                        field = field.name,
+                        offset = field.offset,
+                    );
+                }
+            },
+        }
+
+        self.output.push_str(&text);
+    }
+
+    /// The host type of a struct member. Matrices are laid out by the member
+    /// that contains them, possibly inside arrays
+    fn host_type(&mut self, id: Word, member: (Word, u32), wrapper: &str) -> Result<HostType> {
+        let definition = self
+            .definitions
+            .get(id)
+            .ok_or_else(|| eyre!("Type {id} is not defined"))?;
+
+        match definition.class.opcode {
+            Op::TypeInt | Op::TypeFloat => {
+                let bits = definition.literal_int32(0)?;
+                let signed =
+                    definition.class.opcode == Op::TypeInt && definition.literal_int32(1)? == 1;
+                Ok(self.scalar_type(definition.class.opcode, bits, signed))
+            },
+            // Booleans cannot be stored in blocks, but are accepted as 32 bits
+            Op::TypeBool => Ok(self.scalar_type(Op::TypeInt, 32, false)),
+            // Physical storage buffer pointers are 64-bit device addresses
+            Op::TypePointer => Ok(self.scalar_type(Op::TypeInt, 64, false)),
+            Op::TypeVector => {
+                let component = self.host_type(definition.id_ref(0)?, member, wrapper)?;
+                Ok(component.array(definition.literal_int32(1)?))
+            },
+            Op::TypeMatrix => self.matrix_type(id, member, wrapper),
+            Op::TypeArray => {
+                let length = self.array_length(id)?;
+                let stride = self.array_stride(id)?;
+                let element = self.element_type(
+                    definition.id_ref(0)?,
+                    stride,
+                    member,
+                    &format!("{wrapper}Element"),
+                )?;
+                Ok(element.array(length))
+            },
+            Op::TypeStruct => {
+                let name = self.names.get(&id).cloned().ok_or_else(|| {
+                    eyre!(
+                        "Struct {} is used before it is defined",
+                        self.definitions.name(id)
+                    )
+                })?;
+                let layout = self.layouts.get(&id).copied();
+                let size = self.struct_size(layout, id)?;
+                Ok(HostType {
+                    base: name,
+                    dimensions: Vec::new(),
+                    align: self.alignments.get(&id).copied().unwrap_or(1),
+                    size,
+                    comment: None,
+                })
+            },
+            Op::TypeRuntimeArray => bail!(
+                "{} is a runtime array inside another type",
+                self.definitions.name(id)
+            ),
+            _ => bail!(
+                "{} cannot be represented on the host",
+                self.definitions.name(id)
+            ),
+        }
+    }
+
+    /// The element type of an array, wrapped in a padded struct if the stride
+    /// is larger than the element
+    fn element_type(
+        &mut self,
+        element: Word,
+        stride: u32,
+        member: (Word, u32),
+        wrapper: &str,
+    ) -> Result<HostType> {
+        let element_type = self.host_type(element, member, wrapper)?;
+
+        if stride == element_type.size {
+            return Ok(element_type);
+        }
+        if stride < element_type.size || stride % element_type.align != 0 {
+            bail!(
+                "The array stride {stride} of {} does not fit its {} byte elements",
+                self.definitions.name(element),
+                element_type.size
+            );
+        }
+
+        let name = self.unique_name(wrapper);
+        let align = element_type.align;
+        let size = element_type.size;
+        let value = Field {
+            name: "value".to_string(),
+            ty: element_type,
+            offset: 0,
+            padding: false,
+        };
+        let mut fields = vec![value];
+        fields.extend(self.padding(&fields, size, stride - size));
+        self.write_definition(&name, &fields, stride, false, None);
+
+        Ok(HostType {
+            base: name,
+            dimensions: Vec::new(),
+            align,
+            size: stride,
+            comment: None,
+        })
+    }
+
+    /// A matrix as an array of its columns, or of its rows if it is row major,
+    /// with every vector padded to the matrix stride
+    fn matrix_type(&mut self, id: Word, member: (Word, u32), wrapper: &str) -> Result<HostType> {
+        let (struct_id, index) = member;
+        let layout = self
+            .layouts
+            .get(&struct_id)
+            .and_then(|layout| layout.members.get(index as usize));
+        let stride = layout
+            .and_then(|layout| layout.matrix_stride)
+            .ok_or_else(|| eyre!("Matrix {} has no matrix stride", self.definitions.name(id)))?;
+        let row_major =
+            layout.and_then(|layout| layout.matrix_layout) == Some(MatrixLayout::RowMajor);
+
+        let definition = self
+            .definitions
+            .get(id)
+            .ok_or_else(|| eyre!("Type {id} is not defined"))?;
+        let column_type = definition.id_ref(0)?;
+        let columns = definition.literal_int32(1)?;
+        let column = self
+            .definitions
+            .get_op(column_type, Op::TypeVector)
+            .ok_or_else(|| eyre!("Matrix column {column_type} is not a vector"))?;
+        let rows = column.literal_int32(1)?;
+        let component = self.host_type(column.id_ref(0)?, member, wrapper)?;
+
+        let (count, length) = if row_major {
+            (rows, columns)
+        } else {
+            (columns, rows)
+        };
+        let padded_length = stride / component.size;
+        if stride % component.size != 0 || padded_length < length {
+            bail!(
+                "The matrix stride {stride} of {} does not fit its vectors",
+                self.definitions.name(id)
+            );
+        }
+
+        let mut ty = component.array(padded_length).array(count);
+        ty.comment = Some(format!(
+            "{} {}, {}",
+            self.definitions.name(id),
+            if row_major {
+                "row major"
+            } else {
+                "column major"
+            },
+            if padded_length > length {
+                format!(
+                    "every {} padded to {stride} bytes",
+                    if row_major { "row" } else { "column" }
+                )
+            } else {
+                "tightly packed".to_string()
+            },
+        ));
+        Ok(ty)
+    }
+
+    fn scalar_type(&self, opcode: Op, bits: u32, signed: bool) -> HostType {
+        let size = bits / 8;
+        let base = match (self.language, opcode, signed) {
+            (HostLanguage::Rust, Op::TypeFloat, _) if bits != 16 => format!("f{bits}"),
+            (HostLanguage::Rust, _, true) => format!("i{bits}"),
+            (HostLanguage::Rust, _, false) => format!("u{bits}"),
+            (HostLanguage::Cpp, Op::TypeFloat, _) if bits == 32 => "float".to_string(),
+            (HostLanguage::Cpp, Op::TypeFloat, _) if bits == 64 => "double".to_string(),
+            (HostLanguage::Cpp, _, true) => format!("int{bits}_t"),
+            (HostLanguage::Cpp, _, false) => format!("uint{bits}_t"),
+        };
+        HostType::scalar(&base, size)
+    }
+
+    fn struct_size(&self, layout: Option<&StructLayout>, id: Word) -> Result<u32> {
+        let size = layout.and_then(|layout| layout.size).ok_or_else(|| {
+            eyre!(
+                "Struct {} has no fixed size and cannot be nested",
+                self.definitions.name(id)
+            )
+        })?;
+        Ok(round_up(
+            size,
+            self.alignments.get(&id).copied().unwrap_or(1),
+        ))
+    }
+
+    fn array_length(&self, array: Word) -> Result<u32> {
+        let length = self
+            .definitions
+            .get(array)
+            .ok_or_else(|| eyre!("Type {array} is not defined"))?
+            .id_ref(1)?;
+
+        match self
+            .definitions
+            .get_op(length, Op::Constant)
+            .and_then(|constant| constant.operands.first())
+        {
+            Some(Operand::LiteralBit32(length)) => Ok(*length),
+            _ => bail!(
+                "The length of {} is not a constant",
+                self.definitions.name(array)
+            ),
+        }
+    }
+
+    fn array_stride(&self, array: Word) -> Result<u32> {
+        self.decorations
+            .literal(array, Decoration::ArrayStride)
+            .ok_or_else(|| eyre!("{} has no array stride", self.definitions.name(array)))
+    }
+
+    fn spelling(&self, ty: &HostType) -> String {
+        match self.language {
+            HostLanguage::Rust => ty
+                .dimensions
+                .iter()
+                .rev()
+                .fold(ty.base.clone(), |element, length| {
+                    format!("[{element}; {length}]")
+                }),
+            HostLanguage::Cpp => cpp_declaration(ty, "").replace(' ', ""),
+        }
+    }
+
+    /// A valid identifier that no other generated struct uses
+    fn unique_name(&mut self, name: &str) -> String {
+        let mut name = identifier(name);
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            name = format!("Struct{name}");
+        }
+
+        let mut unique = name.clone();
+        let mut suffix = 1;
+        while !self.used_names.insert(unique.clone()) {
+            suffix += 1;
+            unique = format!("{name}{suffix}");
+        }
+        unique
+    }
+}
+
+/// The array lengths above 32 that bytemuck implements `Pod` for, largest
+/// first. Every shorter array implements it as well
+const POD_ARRAY_LENGTHS: &[u32] = &[4096, 2048, 1024, 512, 256, 128, 96, 64, 48, 32];
+
+fn cpp_declaration(ty: &HostType, name: &str) -> String {
+    let mut declaration = format!("{} {name}", ty.base);
+    for length in ty.dimensions.iter() {
+        let _ = write!(declaration, "[{length}]");
+    }
+    declaration
+}
+
+/// Replaces everything but ASCII letters, digits and underscores, as in HLSL
+/// names like `type.ConstantBuffer.Globals`
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Keywords of either language that are valid shader identifiers
+const KEYWORDS: &[&str] = &[
+    "as",
+    "async",
+    "await",
+    "box",
+    "crate",
+    "dyn",
+    "fn",
+    "impl",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "move",
+    "mut",
+    "pub",
+    "ref",
+    "self",
+    "Self",
+    "super",
+    "trait",
+    "type",
+    "unsafe",
+    "use",
+    "where",
+    "yield",
+    "auto",
+    "bool",
+    "char",
+    "class",
+    "delete",
+    "double",
+    "float",
+    "int",
+    "long",
+    "new",
+    "operator",
+    "private",
+    "protected",
+    "public",
+    "short",
+    "signed",
+    "template",
+    "this",
+    "typename",
+    "unsigned",
+    "virtual",
+    "void",
+];
+
+fn field_name(name: Option<&str>, index: u32) -> String {
+    let name = match name {
+        Some(name) if !name.is_empty() => identifier(name),
+        _ => return format!("member{index}"),
+    };
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) || KEYWORDS.contains(&name.as_str()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+fn upper_first(name: &str) -> String {
+    let mut chars = name.trim_start_matches('_').chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+fn round_up(value: u32, align: u32) -> u32 {
+    let align = align.max(1);
+    (value + align - 1) / align * align
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::assemble;
+
+    fn generate(text: &str, language: HostLanguage) -> String {
+        let module = assemble(text).unwrap();
+        let info = ModuleInfo::create(&module).unwrap();
+        host_structs(&module, &info, language).unwrap()
+    }
+
+    const STD140: &str = r#"
+               OpCapability Shader
+               OpMemoryModel Logical GLSL450
+               OpName %Light "Light"
+               OpMemberName %Light 0 "color"
+               OpMemberName %Light 1 "intensity"
+               OpName %Globals "Globals"
+               OpMemberName %Globals 0 "position"
+               OpMemberName %Globals 1 "scale"
+               OpMemberName %Globals 2 "transform"
+               OpMemberName %Globals 3 "weights"
+               OpMemberName %Globals 4 "light"
+               OpMemberDecorate %Light 0 Offset 0
+               OpMemberDecorate %Light 1 Offset 16
+               OpDecorate %weights ArrayStride 16
+               OpDecorate %Globals Block
+               OpMemberDecorate %Globals 0 Offset 0
+               OpMemberDecorate %Globals 1 Offset 12
+               OpMemberDecorate %Globals 2 Offset 16
+               OpMemberDecorate %Globals 2 ColMajor
+               OpMemberDecorate %Globals 2 MatrixStride 16
+               OpMemberDecorate %Globals 3 Offset 80
+               OpMemberDecorate %Globals 4 Offset 112
+      %float = OpTypeFloat 32
+       %uint = OpTypeInt 32 0
+         %v3 = OpTypeVector %float 3
+         %v4 = OpTypeVector %float 4
+       %mat4 = OpTypeMatrix %v4 4
+     %uint_2 = OpConstant %uint 2
+      %Light = OpTypeStruct %v4 %float
+    %weights = OpTypeArray %float %uint_2
+    %Globals = OpTypeStruct %v3 %float %mat4 %weights %Light
+"#;
+
+    const STD430: &str = r#"
+               OpCapability Shader
+               OpMemoryModel Logical GLSL450
+               OpName %Particle "Particle"
+               OpMemberName %Particle 0 "position"
+               OpMemberName %Particle 1 "velocity"
+               OpName %Particles "Particles"
+               OpMemberName %Particles 0 "gravity"
+               OpMemberName %Particles 1 "mass"
+               OpMemberName %Particles 2 "uv"
+               OpMemberName %Particles 3 "values"
+               OpMemberName %Particles 4 "particles"
+               OpMemberDecorate %Particle 0 Offset 0
+               OpMemberDecorate %Particle 1 Offset 16
+               OpDecorate %values ArrayStride 4
+               OpDecorate %particles ArrayStride 32
+               OpDecorate %Particles Block
+               OpMemberDecorate %Particles 0 Offset 0
+               OpMemberDecorate %Particles 1 Offset 12
+               OpMemberDecorate %Particles 2 Offset 16
+               OpMemberDecorate %Particles 3 Offset 24
+               OpMemberDecorate %Particles 4 Offset 48
+      %float = OpTypeFloat 32
+       %uint = OpTypeInt 32 0
+         %v2 = OpTypeVector %float 2
+         %v3 = OpTypeVector %float 3
+     %uint_3 = OpConstant %uint 3
+   %Particle = OpTypeStruct %v3 %v3
+     %values = OpTypeArray %float %uint_3
+  %particles = OpTypeRuntimeArray %Particle
+  %Particles = OpTypeStruct %v3 %float %v2 %values %particles
+"#;
+
+    /// Structs smaller than 16 bytes in arrays and as a push constant block,
+    /// which std430 does not round up
+    const STD430_SMALL_STRUCTS: &str = r#"
+               OpCapability Shader
+               OpMemoryModel Logical GLSL450
+               OpName %Point "Point"
+               OpMemberName %Point 0 "p"
+               OpName %Pair "Pair"
+               OpMemberName %Pair 0 "a"
+               OpMemberName %Pair 1 "b"
+               OpName %Shapes "Shapes"
+               OpMemberName %Shapes 0 "pairs"
+               OpMemberName %Shapes 1 "count"
+               OpMemberName %Shapes 2 "points"
+               OpName %Push "Push"
+               OpMemberName %Push 0 "scale"
+               OpMemberName %Push 1 "bias"
+               OpMemberDecorate %Point 0 Offset 0
+               OpMemberDecorate %Pair 0 Offset 0
+               OpMemberDecorate %Pair 1 Offset 4
+               OpDecorate %pairs ArrayStride 8
+               OpDecorate %points ArrayStride 8
+               OpDecorate %Shapes BufferBlock
+               OpMemberDecorate %Shapes 0 Offset 0
+               OpMemberDecorate %Shapes 1 Offset 24
+               OpMemberDecorate %Shapes 2 Offset 32
+               OpDecorate %Push Block
+               OpMemberDecorate %Push 0 Offset 0
+               OpMemberDecorate %Push 1 Offset 4
+      %float = OpTypeFloat 32
+       %uint = OpTypeInt 32 0
+         %v2 = OpTypeVector %float 2
+     %uint_3 = OpConstant %uint 3
+      %Point = OpTypeStruct %v2
+       %Pair = OpTypeStruct %float %float
+      %pairs = OpTypeArray %Pair %uint_3
+     %points = OpTypeRuntimeArray %Point
+     %Shapes = OpTypeStruct %pairs %uint %points
+       %Push = OpTypeStruct %float %float
+"#;
+
+    #[test]
+    fn std140_rust() {
+        assert_eq!(generate(STD140, HostLanguage::Rust), STD140_RUST);
+    }
+
+    #[test]
+    fn std140_cpp() {
+        assert_eq!(generate(STD140, HostLanguage::Cpp), STD140_CPP);
+    }
+
+    #[test]
+    fn std430_rust() {
+        assert_eq!(generate(STD430, HostLanguage::Rust), STD430_RUST);
+    }
+
+    #[test]
+    fn std430_cpp() {
+        assert_eq!(generate(STD430, HostLanguage::Cpp), STD430_CPP);
+    }
+
+    const STD140_CPP: &str = r#"#include <cstddef>
+#include <cstdint>
+
+struct Light {
+    float color[4];
+    float intensity;
+    uint8_t _pad0[12];
+};
+static_assert(sizeof(Light) == 32, "Light must be 32 bytes");
+static_assert(offsetof(Light, color) == 0, "Light::color must be at offset 0");
+static_assert(offsetof(Light, intensity) == 16, "Light::intensity must be at offset 16");
+
+struct GlobalsWeightsElement {
+    float value;
+    uint8_t _pad0[12];
+};
+static_assert(sizeof(GlobalsWeightsElement) == 16, "GlobalsWeightsElement must be 16 bytes");
+static_assert(offsetof(GlobalsWeightsElement, value) == 0, "GlobalsWeightsElement::value must be at offset 0");
+
+struct Globals {
+    float position[3];
+    float scale;
+    // 4x4xf32 column major, tightly packed
+    float transform[4][4];
+    GlobalsWeightsElement weights[2];
+    Light light;
+};
+static_assert(sizeof(Globals) == 144, "Globals must be 144 bytes");
+static_assert(offsetof(Globals, position) == 0, "Globals::position must be at offset 0");
+static_assert(offsetof(Globals, scale) == 12, "Globals::scale must be at offset 12");
+static_assert(offsetof(Globals, transform) == 16, "Globals::transform must be at offset 16");
+static_assert(offsetof(Globals, weights) == 80, "Globals::weights must be at offset 80");
+static_assert(offsetof(Globals, light) == 112, "Globals::light must be at offset 112");
+"#;
+
+    #[test]
+    fn std430_small_structs_rust() {
+        assert_eq!(
+            generate(STD430_SMALL_STRUCTS, HostLanguage::Rust),
+            STD430_SMALL_STRUCTS_RUST
+        );
+    }
+
+    #[test]
+    fn std430_small_structs_cpp() {
+        assert_eq!(
+            generate(STD430_SMALL_STRUCTS, HostLanguage::Cpp),
+            STD430_SMALL_STRUCTS_CPP
+        );
+    }
+
+    #[test]
+    fn padding_is_split_into_pod_arrays() {
+        let text = r#"
+               OpCapability Shader
+               OpMemoryModel Logical GLSL450
+               OpName %Block "Block"
+               OpMemberName %Block 0 "first"
+               OpMemberName %Block 1 "second"
+               OpMemberName %Block 2 "values"
+               OpDecorate %values ArrayStride 4
+               OpDecorate %Block Block
+               OpMemberDecorate %Block 0 Offset 0
+               OpMemberDecorate %Block 1 Offset 40
+               OpMemberDecorate %Block 2 Offset 44
+      %float = OpTypeFloat 32
+       %uint = OpTypeInt 32 0
+   %uint_100 = OpConstant %uint 100
+     %values = OpTypeArray %float %uint_100
+      %Block = OpTypeStruct %float %float %values
+"#;
+        let rust = generate(text, HostLanguage::Rust);
+        assert!(rust.contains(
+            "    pub first: f32,\n    pub _pad0: [u8; 32],\n    pub _pad1: [u8; 4],\n    pub second: f32,\n"
+        ));
+        assert!(rust.starts_with(
+            "use bytemuck::{Pod, Zeroable};\n\n// Arrays of length 100 implement Pod only with the \
+             `min_const_generics` feature of bytemuck\n"
+        ));
+
+        // C++ has no such restriction
+        let cpp = generate(text, HostLanguage::Cpp);
+        assert!(cpp.contains("    float first;\n    uint8_t _pad0[36];\n    float second;\n"));
+    }
+
+    const STD140_RUST: &str = r#"use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Light {
+    pub color: [f32; 4],
+    pub intensity: f32,
+    pub _pad0: [u8; 12],
+}
+const _: () = assert!(std::mem::size_of::<Light>() == 32);
+const _: () = assert!(std::mem::offset_of!(Light, color) == 0);
+const _: () = assert!(std::mem::offset_of!(Light, intensity) == 16);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct GlobalsWeightsElement {
+    pub value: f32,
+    pub _pad0: [u8; 12],
+}
+const _: () = assert!(std::mem::size_of::<GlobalsWeightsElement>() == 16);
+const _: () = assert!(std::mem::offset_of!(GlobalsWeightsElement, value) == 0);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Globals {
+    pub position: [f32; 3],
+    pub scale: f32,
+    // 4x4xf32 column major, tightly packed
+    pub transform: [[f32; 4]; 4],
+    pub weights: [GlobalsWeightsElement; 2],
+    pub light: Light,
+}
+const _: () = assert!(std::mem::size_of::<Globals>() == 144);
+const _: () = assert!(std::mem::offset_of!(Globals, position) == 0);
+const _: () = assert!(std::mem::offset_of!(Globals, scale) == 12);
+const _: () = assert!(std::mem::offset_of!(Globals, transform) == 16);
+const _: () = assert!(std::mem::offset_of!(Globals, weights) == 80);
+const _: () = assert!(std::mem::offset_of!(Globals, light) == 112);
+"#;
+
+    const STD430_CPP: &str = r#"#include <cstddef>
+#include <cstdint>
+
+struct Particle {
+    float position[3];
+    uint8_t _pad0[4];
+    float velocity[3];
+    uint8_t _pad1[4];
+};
+static_assert(sizeof(Particle) == 32, "Particle must be 32 bytes");
+static_assert(offsetof(Particle, position) == 0, "Particle::position must be at offset 0");
+static_assert(offsetof(Particle, velocity) == 16, "Particle::velocity must be at offset 16");
+
+struct Particles {
+    float gravity[3];
+    float mass;
+    float uv[2];
+    float values[3];
+    uint8_t _pad0[12];
+    // Followed by particles, a runtime array of Particle at offset 48
+};
+static_assert(sizeof(Particles) == 48, "Particles must be 48 bytes");
+static_assert(offsetof(Particles, gravity) == 0, "Particles::gravity must be at offset 0");
+static_assert(offsetof(Particles, mass) == 12, "Particles::mass must be at offset 12");
+static_assert(offsetof(Particles, uv) == 16, "Particles::uv must be at offset 16");
+static_assert(offsetof(Particles, values) == 24, "Particles::values must be at offset 24");
+"#;
+
+    const STD430_RUST: &str = r#"use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Particle {
+    pub position: [f32; 3],
+    pub _pad0: [u8; 4],
+    pub velocity: [f32; 3],
+    pub _pad1: [u8; 4],
+}
+const _: () = assert!(std::mem::size_of::<Particle>() == 32);
+const _: () = assert!(std::mem::offset_of!(Particle, position) == 0);
+const _: () = assert!(std::mem::offset_of!(Particle, velocity) == 16);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Particles {
+    pub gravity: [f32; 3],
+    pub mass: f32,
+    pub uv: [f32; 2],
+    pub values: [f32; 3],
+    pub _pad0: [u8; 12],
+    // Followed by particles, a runtime array of Particle at offset 48
+}
+const _: () = assert!(std::mem::size_of::<Particles>() == 48);
+const _: () = assert!(std::mem::offset_of!(Particles, gravity) == 0);
+const _: () = assert!(std::mem::offset_of!(Particles, mass) == 12);
+const _: () = assert!(std::mem::offset_of!(Particles, uv) == 16);
+const _: () = assert!(std::mem::offset_of!(Particles, values) == 24);
+"#;
+
+    const STD430_SMALL_STRUCTS_RUST: &str = r#"use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Point {
+    pub p: [f32; 2],
+}
+const _: () = assert!(std::mem::size_of::<Point>() == 8);
+const _: () = assert!(std::mem::offset_of!(Point, p) == 0);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Pair {
+    pub a: f32,
+    pub b: f32,
+}
+const _: () = assert!(std::mem::size_of::<Pair>() == 8);
+const _: () = assert!(std::mem::offset_of!(Pair, a) == 0);
+const _: () = assert!(std::mem::offset_of!(Pair, b) == 4);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Shapes {
+    pub pairs: [Pair; 3],
+    pub count: u32,
+    pub _pad0: [u8; 4],
+    // Followed by points, a runtime array of Point at offset 32
+}
+const _: () = assert!(std::mem::size_of::<Shapes>() == 32);
+const _: () = assert!(std::mem::offset_of!(Shapes, pairs) == 0);
+const _: () = assert!(std::mem::offset_of!(Shapes, count) == 24);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Push {
+    pub scale: f32,
+    pub bias: f32,
+}
+const _: () = assert!(std::mem::size_of::<Push>() == 8);
+const _: () = assert!(std::mem::offset_of!(Push, scale) == 0);
+const _: () = assert!(std::mem::offset_of!(Push, bias) == 4);
+"#;
+
+    const STD430_SMALL_STRUCTS_CPP: &str = r#"#include <cstddef>
+#include <cstdint>
+
+struct Point {
+    float p[2];
+};
+static_assert(sizeof(Point) == 8, "Point must be 8 bytes");
+static_assert(offsetof(Point, p) == 0, "Point::p must be at offset 0");
+
+struct Pair {
+    float a;
+    float b;
+};
+static_assert(sizeof(Pair) == 8, "Pair must be 8 bytes");
+static_assert(offsetof(Pair, a) == 0, "Pair::a must be at offset 0");
+static_assert(offsetof(Pair, b) == 4, "Pair::b must be at offset 4");
+
+struct Shapes {
+    Pair pairs[3];
+    uint32_t count;
+    uint8_t _pad0[4];
+    // Followed by points, a runtime array of Point at offset 32
+};
+static_assert(sizeof(Shapes) == 32, "Shapes must be 32 bytes");
+static_assert(offsetof(Shapes, pairs) == 0, "Shapes::pairs must be at offset 0");
+static_assert(offsetof(Shapes, count) == 24, "Shapes::count must be at offset 24");
+
+struct Push {
+    float scale;
+    float bias;
+};
+static_assert(sizeof(Push) == 8, "Push must be 8 bytes");
+static_assert(offsetof(Push, scale) == 0, "Push::scale must be at offset 0");
+static_assert(offsetof(Push, bias) == 4, "Push::bias must be at offset 4");
+"#;
+}
@@ -0,0 +1,239 @@
+use itertools::Itertools;
+use shaderc::{IncludeCallbackResult, IncludeType, ResolvedInclude};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+pub const DEFAULT_MAX_INCLUDE_DEPTH: usize = 32;
+
+/// Resolves `#include` directives against a set of in-memory files and on-disk
+/// include directories.
+///
+/// In-memory files are addressed by normalized, `/`-separated virtual paths.
+/// They take precedence over files on disk.
+pub struct IncludeResolver {
+    sources: BTreeMap<String, String>,
+    include_directories: Vec<PathBuf>,
+    max_depth: usize,
+}
+
+impl IncludeResolver {
+    pub fn new(
+        sources: &BTreeMap<String, String>,
+        include_directories: &[String],
+        max_depth: Option<usize>,
+    ) -> Self {
+        Self {
+            sources: sources
+                .iter()
+                .map(|(name, content)| (normalize_virtual_path(name), content.clone()))
+                .collect(),
+            include_directories: include_directories.iter().map(PathBuf::from).collect(),
+            max_depth: max_depth.unwrap_or(DEFAULT_MAX_INCLUDE_DEPTH),
+        }
+    }
+
+    pub fn resolve(
+        &self,
+        requested: &str,
+        include_type: IncludeType,
+        requesting: &str,
+        depth: usize,
+    ) -> IncludeCallbackResult {
+        if depth > self.max_depth {
+            return Err(format!(
+                "Cannot include \"{requested}\" from \"{requesting}\": include depth limit of {} \
+                 exceeded",
+                self.max_depth
+            ));
+        }
+
+        // Like glslc and GCC, quoted includes that are not next to the including
+        // file are searched for like angle bracket includes
+        let resolved = match include_type {
+            IncludeType::Relative => self
+                .resolve_relative(requested, requesting)
+                .or_else(|| self.resolve_standard(requested)),
+            IncludeType::Standard => self.resolve_standard(requested),
+        };
+
+        resolved.ok_or_else(|| {
+            let searched = self
+                .include_directories
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .join(", ");
+            let requested = match include_type {
+                IncludeType::Relative => format!("\"{requested}\" relative to \"{requesting}\""),
+                IncludeType::Standard => format!("<{requested}>"),
+            };
+            if searched.is_empty() {
+                format!(
+                    "Cannot find include file {requested} in the provided sources (no include \
+                     directories given)"
+                )
+            } else {
+                format!(
+                    "Cannot find include file {requested} in the provided sources or include \
+                     directories: {searched}"
+                )
+            }
+        })
+    }
+
+    fn resolve_relative(&self, requested: &str, requesting: &str) -> Option<ResolvedInclude> {
+        let requesting_path = Path::new(requesting);
+
+        // Files from include directories are named by their real path, so their
+        // relative includes are looked up next to them on disk
+        if requesting_path.is_absolute() {
+            let path = requesting_path.parent()?.join(requested);
+            return Self::read_from_disk(&path);
+        }
+
+        let directory = match requesting.rfind(['/', '\\']) {
+            Some(index) => &requesting[..index],
+            None => "",
+        };
+        self.read_virtual(&normalize_virtual_path(&format!("{directory}/{requested}")))
+    }
+
+    fn resolve_standard(&self, requested: &str) -> Option<ResolvedInclude> {
+        self.read_virtual(&normalize_virtual_path(requested))
+            .or_else(|| {
+                self.include_directories
+                    .iter()
+                    .find_map(|directory| Self::read_from_disk(&directory.join(requested)))
+            })
+    }
+
+    fn read_virtual(&self, name: &str) -> Option<ResolvedInclude> {
+        self.sources.get(name).map(|content| ResolvedInclude {
+            resolved_name: name.to_string(),
+            content: content.clone(),
+        })
+    }
+
+    fn read_from_disk(path: &Path) -> Option<ResolvedInclude> {
+        let path = path.canonicalize().ok()?;
+        let content = std::fs::read_to_string(&path).ok()?;
+
+        Some(ResolvedInclude {
+            resolved_name: path.to_string_lossy().into_owned(),
+            content,
+        })
+    }
+}
+
+/// Collapses `.` and `..` components and unifies separators, so that
+/// `a/./b/../c.glsl` and `a\c.glsl` both name the same virtual file `a/c.glsl`
+pub fn normalize_virtual_path(path: &str) -> String {
+    let mut components = Vec::new();
+
+    for component in path.split(['/', '\\']) {
+        match component {
+            "" | "." => (),
+            ".." => {
+                components.pop();
+            },
+            component => components.push(component),
+        }
+    }
+
+    components.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver(sources: &[(&str, &str)], include_directories: &[String]) -> IncludeResolver {
+        let sources = sources
+            .iter()
+            .map(|(name, content)| (name.to_string(), content.to_string()))
+            .collect();
+        IncludeResolver::new(&sources, include_directories, None)
+    }
+
+    #[test]
+    fn normalize_collapses_components() {
+        assert_eq!(normalize_virtual_path("a/./b/../c.glsl"), "a/c.glsl");
+        assert_eq!(normalize_virtual_path("a\\c.glsl"), "a/c.glsl");
+        assert_eq!(normalize_virtual_path("/a//b/"), "a/b");
+        assert_eq!(normalize_virtual_path(""), "");
+    }
+
+    #[test]
+    fn normalize_clamps_parent_of_root() {
+        assert_eq!(normalize_virtual_path("../a.glsl"), "a.glsl");
+        assert_eq!(normalize_virtual_path("a/../../../b/c.glsl"), "b/c.glsl");
+        assert_eq!(normalize_virtual_path(".."), "");
+    }
+
+    #[test]
+    fn relative_prefers_the_including_directory() {
+        let resolver = resolver(&[("lib/common.glsl", "near"), ("common.glsl", "root")], &[]);
+        let resolved = resolver
+            .resolve("common.glsl", IncludeType::Relative, "lib/main.glsl", 1)
+            .unwrap();
+        assert_eq!(resolved.resolved_name, "lib/common.glsl");
+        assert_eq!(resolved.content, "near");
+    }
+
+    #[test]
+    fn relative_falls_back_to_standard_lookup() {
+        let resolver = resolver(&[("common.glsl", "root")], &[]);
+        let resolved = resolver
+            .resolve("common.glsl", IncludeType::Relative, "lib/main.glsl", 1)
+            .unwrap();
+        assert_eq!(resolved.resolved_name, "common.glsl");
+        assert_eq!(resolved.content, "root");
+    }
+
+    #[test]
+    fn relative_falls_back_to_include_directories() {
+        let directory = std::env::temp_dir().join(format!(
+            "shaderc-interactive-includes-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("disk.glsl"), "disk").unwrap();
+
+        let resolver = resolver(&[], &[directory.to_string_lossy().into_owned()]);
+        let resolved = resolver.resolve("disk.glsl", IncludeType::Relative, "main.glsl", 1);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(resolved.unwrap().content, "disk");
+    }
+
+    #[test]
+    fn missing_relative_include_is_an_error() {
+        let resolver = resolver(&[("main.glsl", "")], &[]);
+        let error = resolver
+            .resolve("missing.glsl", IncludeType::Relative, "main.glsl", 1)
+            .unwrap_err();
+        assert!(error.contains("\"missing.glsl\" relative to \"main.glsl\""));
+    }
+
+    #[test]
+    fn depth_limit() {
+        let resolver = resolver(&[("a.glsl", "")], &[]);
+        assert!(resolver
+            .resolve(
+                "a.glsl",
+                IncludeType::Standard,
+                "main.glsl",
+                DEFAULT_MAX_INCLUDE_DEPTH
+            )
+            .is_ok());
+        assert!(resolver
+            .resolve(
+                "a.glsl",
+                IncludeType::Standard,
+                "main.glsl",
+                DEFAULT_MAX_INCLUDE_DEPTH + 1
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,858 @@
+use crate::{
+    operands::InstructionOperands,
+    reflection::{Decorations, Definitions},
+};
+use eyre::Result;
+use rspirv::dr::{Module, Operand};
+use serde::{Deserialize, Serialize};
+use spirv::{Decoration, Op, Word};
+use std::collections::HashMap;
+
+/// The memory layout of a struct as given by its decorations
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StructLayout {
+    pub id: Word,
+    pub name: String,
+    pub members: Vec<MemberLayout>,
+    /// Missing if the struct ends in a runtime array or is not explicitly laid
+    /// out. Rounded up to the alignment of the struct under the rule it is laid
+    /// out by, which is the space it takes when nested or in an array
+    pub size: Option<u32>,
+    /// Unused bytes between members and at the end
+    pub padding: Vec<Padding>,
+    /// The standard layouts that produce exactly these offsets and strides
+    pub rules: Vec<LayoutRule>,
+    /// Places where a C struct with the same members would be laid out
+    /// differently
+    pub warnings: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MemberLayout {
+    pub index: u32,
+    pub name: Option<String>,
+    pub type_name: String,
+    pub offset: Option<u32>,
+    /// Missing for runtime arrays
+    pub size: Option<u32>,
+    pub array_stride: Option<u32>,
+    pub matrix_stride: Option<u32>,
+    /// Only set for matrices and arrays of matrices
+    pub matrix_layout: Option<MatrixLayout>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatrixLayout {
+    ColumnMajor,
+    RowMajor,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Padding {
+    pub offset: u32,
+    pub size: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LayoutRule {
+    Std140,
+    Std430,
+    /// `VK_EXT_scalar_block_layout`, which is also how C lays out a struct of
+    /// scalars and arrays
+    Scalar,
+}
+
+impl LayoutRule {
+    const ALL: [Self; 3] = [Self::Std140, Self::Std430, Self::Scalar];
+    /// The order in which a struct takes a rule when several produce its
+    /// offsets. std140 would only add padding at the end that nothing relies on
+    const PREFERENCE: [Self; 3] = [Self::Std430, Self::Std140, Self::Scalar];
+}
+
+/// Lists every struct type in declaration order
+pub fn struct_layouts(
+    module: &Module,
+    definitions: &Definitions,
+    member_names: &HashMap<(Word, u32), String>,
+    decorations: &Decorations,
+) -> Result<Vec<StructLayout>> {
+    let mut layouts = Layouts {
+        definitions,
+        decorations,
+        struct_rules: HashMap::new(),
+    };
+    layouts.struct_rules = layouts.struct_rules(module);
+
+    module
+        .types_global_values
+        .iter()
+        .filter(|instruction| instruction.class.opcode == Op::TypeStruct)
+        .map(|instruction| {
+            let id = instruction.result_id()?;
+
+            let members = (0..instruction.operands.len())
+                .map(|index| {
+                    let member = index as u32;
+                    let member_type = instruction.id_ref(index)?;
+                    let context = Some((id, member));
+
+                    let is_matrix = definitions
+                        .get_op(layouts.innermost_element(member_type), Op::TypeMatrix)
+                        .is_some();
+
+                    Ok(MemberLayout {
+                        index: member,
+                        name: member_names.get(&(id, member)).cloned(),
+                        type_name: definitions.name(member_type),
+                        offset: decorations.member_literal(id, member, Decoration::Offset),
+                        size: layouts.size(member_type, context),
+                        array_stride: decorations.literal(member_type, Decoration::ArrayStride),
+                        matrix_stride: decorations.member_literal(
+                            id,
+                            member,
+                            Decoration::MatrixStride,
+                        ),
+                        matrix_layout: is_matrix.then(|| layouts.matrix_layout(context)),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let mut layout = StructLayout {
+                id,
+                name: definitions.name(id),
+                size: layouts.size(id, None),
+                members,
+                padding: Vec::new(),
+                rules: Vec::new(),
+                warnings: Vec::new(),
+            };
+
+            // Interface blocks like gl_PerVertex have no explicit layout
+            if layout.members.iter().all(|member| member.offset.is_some()) {
+                layouts.check(&mut layout);
+            }
+
+            Ok(layout)
+        })
+        .collect()
+}
+
+/// The alignment and size of a type under a standard layout rule
+#[derive(Clone, Copy)]
+struct RuleLayout {
+    align: u32,
+    size: u32,
+}
+
+struct Layouts<'a, 'm> {
+    definitions: &'a Definitions<'m>,
+    decorations: &'a Decorations,
+    /// The rule each struct is laid out by, see [`Layouts::struct_rules`]
+    struct_rules: HashMap<Word, LayoutRule>,
+}
+
+impl Layouts<'_, '_> {
+    /// Picks the rule of every struct from where it is used. The stride of an
+    /// array of structs gives the rule away, and nested structs follow the
+    /// struct that contains them. Other structs take the first rule in
+    /// [`LayoutRule::PREFERENCE`] that produces their offsets, or are laid out
+    /// like in C if none does
+    fn struct_rules(&self, module: &Module) -> HashMap<Word, LayoutRule> {
+        let types = &module.types_global_values;
+        let mut rules = HashMap::new();
+
+        // Types are declared before they are used, so the structs containing
+        // a struct are visited before it
+        for instruction in types.iter().rev() {
+            if instruction.class.opcode != Op::TypeStruct {
+                continue;
+            }
+            let id = match instruction.result_id {
+                Some(id) => id,
+                None => continue,
+            };
+            let matched = LayoutRule::PREFERENCE
+                .iter()
+                .copied()
+                .filter(|&rule| self.matches(rule, id))
+                .collect::<Vec<_>>();
+
+            let from_stride = types
+                .iter()
+                .filter(|array| {
+                    array.class.opcode == Op::TypeArray
+                        || array.class.opcode == Op::TypeRuntimeArray
+                })
+                .filter(|array| array.operands.first() == Some(&Operand::IdRef(id)))
+                .filter_map(|array| {
+                    self.decorations
+                        .literal(array.result_id?, Decoration::ArrayStride)
+                })
+                .find_map(|stride| {
+                    matched.iter().copied().find(|&rule| {
+                        self.rule_layout(rule, id, false)
+                            .map(|layout| array_layout(rule, layout, 0).1)
+                            == Some(stride)
+                    })
+                });
+            let from_parent = types
+                .iter()
+                .filter(|parent| parent.class.opcode == Op::TypeStruct)
+                .filter(|parent| {
+                    parent.operands.iter().any(|member| match member {
+                        Operand::IdRef(member) => self.innermost_element(*member) == id,
+                        _ => false,
+                    })
+                })
+                .filter_map(|parent| rules.get(&parent.result_id?).copied())
+                .find(|rule| matched.contains(rule));
+
+            let rule = from_stride
+                .or(from_parent)
+                .or_else(|| matched.first().copied())
+                .unwrap_or(LayoutRule::Scalar);
+            rules.insert(id, rule);
+        }
+
+        rules
+    }
+
+    fn innermost_element(&self, mut id: Word) -> Word {
+        while let Some(array) = self.definitions.get(id) {
+            match (array.class.opcode, array.operands.first()) {
+                (Op::TypeArray | Op::TypeRuntimeArray, Some(Operand::IdRef(element))) => {
+                    id = *element
+                },
+                _ => break,
+            }
+        }
+        id
+    }
+
+    fn array_length(&self, array: Word) -> Option<u32> {
+        let length = self.definitions.get(array)?.id_ref(1).ok()?;
+        match self
+            .definitions
+            .get_op(length, Op::Constant)?
+            .operands
+            .first()?
+        {
+            Operand::LiteralBit32(length) => Some(*length),
+            _ => None,
+        }
+    }
+
+    /// Matrices are column major unless the struct member says otherwise
+    fn matrix_layout(&self, member: Option<(Word, u32)>) -> MatrixLayout {
+        match member {
+            Some((id, member))
+                if self
+                    .decorations
+                    .member(id, member, Decoration::RowMajor)
+                    .is_some() =>
+            {
+                MatrixLayout::RowMajor
+            },
+            _ => MatrixLayout::ColumnMajor,
+        }
+    }
+
+    /// The size of a type according to its decorations. Matrices are laid out
+    /// by the struct member that contains them, possibly inside arrays
+    fn size(&self, id: Word, member: Option<(Word, u32)>) -> Option<u32> {
+        let definition = self.definitions.get(id)?;
+
+        match definition.class.opcode {
+            Op::TypeInt | Op::TypeFloat => Some(definition.literal_int32(0).ok()? / 8),
+            Op::TypeBool => Some(4),
+            Op::TypePointer => Some(8),
+            Op::TypeVector => {
+                let component = self.size(definition.id_ref(0).ok()?, None)?;
+                Some(component * definition.literal_int32(1).ok()?)
+            },
+            Op::TypeMatrix => {
+                let column_type = self.definitions.get(definition.id_ref(0).ok()?)?;
+                let columns = definition.literal_int32(1).ok()?;
+                let rows = column_type.literal_int32(1).ok()?;
+                let component = self.size(column_type.id_ref(0).ok()?, None)?;
+
+                let (vectors, vector_size) = match self.matrix_layout(member) {
+                    MatrixLayout::ColumnMajor => (columns, rows * component),
+                    MatrixLayout::RowMajor => (rows, columns * component),
+                };
+                let stride = member
+                    .and_then(|(id, member)| {
+                        self.decorations
+                            .member_literal(id, member, Decoration::MatrixStride)
+                    })
+                    .unwrap_or(vector_size);
+                Some(vectors * stride)
+            },
+            Op::TypeArray => {
+                let element = definition.id_ref(0).ok()?;
+                let stride = match self.decorations.literal(id, Decoration::ArrayStride) {
+                    Some(stride) => stride,
+                    None => self.size(element, member)?,
+                };
+                Some(self.array_length(id)? * stride)
+            },
+            Op::TypeStruct => {
+                let end = (0..definition.operands.len())
+                    .map(|index| {
+                        let member = index as u32;
+                        let offset =
+                            self.decorations
+                                .member_literal(id, member, Decoration::Offset)?;
+                        let size = self.size(definition.id_ref(index).ok()?, Some((id, member)))?;
+                        Some(offset + size)
+                    })
+                    .try_fold(0, |end, member_end| Some(end.max(member_end?)))?;
+                Some(round_up(end, self.struct_align(id)))
+            },
+            _ => None,
+        }
+    }
+
+    /// The alignment of a struct under the rule it is laid out by. Only std140
+    /// rounds it up to 16 bytes
+    fn struct_align(&self, id: Word) -> u32 {
+        let rule = self
+            .struct_rules
+            .get(&id)
+            .copied()
+            .unwrap_or(LayoutRule::Scalar);
+        self.rule_layout(rule, id, false)
+            .map_or(1, |layout| layout.align)
+    }
+
+    /// The layout a standard rule gives a type. Runtime arrays have size 0
+    fn rule_layout(&self, rule: LayoutRule, id: Word, row_major: bool) -> Option<RuleLayout> {
+        let definition = self.definitions.get(id)?;
+
+        match definition.class.opcode {
+            Op::TypeInt | Op::TypeFloat | Op::TypeBool | Op::TypePointer => {
+                let size = self.size(id, None)?;
+                Some(RuleLayout { align: size, size })
+            },
+            Op::TypeVector => {
+                let component = self.size(definition.id_ref(0).ok()?, None)?;
+                let count = definition.literal_int32(1).ok()?;
+                Some(vector_layout(rule, component, count))
+            },
+            Op::TypeMatrix => {
+                let (_, vector, count) = self.matrix_vectors(rule, id, row_major)?;
+                Some(array_layout(rule, vector, count).0)
+            },
+            Op::TypeArray | Op::TypeRuntimeArray => {
+                let element = self.rule_layout(rule, definition.id_ref(0).ok()?, row_major)?;
+                let length = match definition.class.opcode {
+                    Op::TypeArray => self.array_length(id)?,
+                    _ => 0,
+                };
+                Some(array_layout(rule, element, length).0)
+            },
+            Op::TypeStruct => {
+                let mut end = 0;
+                let mut align = 1;
+                for index in 0..definition.operands.len() {
+                    let member = self.rule_layout(
+                        rule,
+                        definition.id_ref(index).ok()?,
+                        self.matrix_layout(Some((id, index as u32))) == MatrixLayout::RowMajor,
+                    )?;
+                    end = round_up(end, member.align) + member.size;
+                    align = align.max(member.align);
+                }
+                if rule == LayoutRule::Std140 {
+                    align = round_up(align, 16);
+                }
+                Some(RuleLayout {
+                    align,
+                    size: round_up(end, align),
+                })
+            },
+            _ => None,
+        }
+    }
+
+    /// The stride, vector layout and vector count of a matrix under a rule
+    fn matrix_vectors(
+        &self,
+        rule: LayoutRule,
+        id: Word,
+        row_major: bool,
+    ) -> Option<(u32, RuleLayout, u32)> {
+        let definition = self.definitions.get(id)?;
+        let column_type = self.definitions.get(definition.id_ref(0).ok()?)?;
+        let columns = definition.literal_int32(1).ok()?;
+        let rows = column_type.literal_int32(1).ok()?;
+        let component = self.size(column_type.id_ref(0).ok()?, None)?;
+
+        let (count, length) = if row_major {
+            (rows, columns)
+        } else {
+            (columns, rows)
+        };
+        let vector = vector_layout(rule, component, length);
+        let (_, stride) = array_layout(rule, vector, count);
+        Some((stride, vector, count))
+    }
+
+    /// Whether a rule reproduces the offsets and strides of a struct
+    fn matches(&self, rule: LayoutRule, id: Word) -> bool {
+        let definition = match self.definitions.get(id) {
+            Some(definition) => definition,
+            None => return false,
+        };
+
+        let mut end = 0;
+        for index in 0..definition.operands.len() {
+            let member = index as u32;
+            let member_type = match definition.id_ref(index) {
+                Ok(member_type) => member_type,
+                Err(_) => return false,
+            };
+            let row_major = self.matrix_layout(Some((id, member))) == MatrixLayout::RowMajor;
+
+            let layout = match self.rule_layout(rule, member_type, row_major) {
+                Some(layout) => layout,
+                None => return false,
+            };
+            let offset = round_up(end, layout.align);
+            end = offset + layout.size;
+
+            if self
+                .decorations
+                .member_literal(id, member, Decoration::Offset)
+                != Some(offset)
+            {
+                return false;
+            }
+
+            let matrix_stride =
+                self.decorations
+                    .member_literal(id, member, Decoration::MatrixStride);
+            if !self.matches_type(rule, member_type, row_major, matrix_stride) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn matches_type(
+        &self,
+        rule: LayoutRule,
+        id: Word,
+        row_major: bool,
+        matrix_stride: Option<u32>,
+    ) -> bool {
+        let definition = match self.definitions.get(id) {
+            Some(definition) => definition,
+            None => return false,
+        };
+
+        match definition.class.opcode {
+            Op::TypeArray | Op::TypeRuntimeArray => {
+                let element = match definition.id_ref(0) {
+                    Ok(element) => element,
+                    Err(_) => return false,
+                };
+                let stride = self
+                    .rule_layout(rule, element, row_major)
+                    .map(|element| array_layout(rule, element, 0).1);
+
+                stride.is_some()
+                    && self.decorations.literal(id, Decoration::ArrayStride) == stride
+                    && self.matches_type(rule, element, row_major, matrix_stride)
+            },
+            Op::TypeMatrix => {
+                self.matrix_vectors(rule, id, row_major)
+                    .map(|(stride, ..)| stride)
+                    == matrix_stride
+            },
+            Op::TypeStruct => self.matches(rule, id),
+            _ => true,
+        }
+    }
+
+    /// Fills in padding, matching rules and the differences to a C struct
+    fn check(&self, layout: &mut StructLayout) {
+        layout.rules = LayoutRule::ALL
+            .iter()
+            .copied()
+            .filter(|&rule| self.matches(rule, layout.id))
+            .collect();
+        if layout.rules.is_empty() {
+            layout
+                .warnings
+                .push("The layout matches none of std140, std430 or scalar".to_string());
+        }
+
+        let definition = match self.definitions.get(layout.id) {
+            Some(definition) => definition,
+            None => return,
+        };
+
+        let mut members = layout.members.iter().collect::<Vec<_>>();
+        members.sort_by_key(|member| member.offset);
+
+        let mut end = 0;
+        for member in members {
+            let offset = member.offset.unwrap_or_default();
+            let name = member
+                .name
+                .clone()
+                .unwrap_or_else(|| member.index.to_string());
+
+            if offset > end {
+                layout.padding.push(Padding {
+                    offset: end,
+                    size: offset - end,
+                });
+            } else if offset < end {
+                layout.warnings.push(format!(
+                    "Member {name} at {offset} overlaps the previous member"
+                ));
+            }
+
+            // A C compiler places the member at the next multiple of its
+            // alignment
+            let member_type = match definition.id_ref(member.index as usize) {
+                Ok(member_type) => member_type,
+                Err(_) => continue,
+            };
+            let row_major = member.matrix_layout == Some(MatrixLayout::RowMajor);
+            if let Some(c_layout) = self.rule_layout(LayoutRule::Scalar, member_type, row_major) {
+                let c_offset = round_up(end, c_layout.align);
+                if c_offset != offset {
+                    layout.warnings.push(format!(
+                        "Member {name} is at offset {offset}, a C struct would place it at \
+                         {c_offset}"
+                    ));
+                }
+
+                if let (Some(stride), Some(c_stride)) = (
+                    member.array_stride,
+                    self.c_array_stride(member_type, row_major),
+                ) {
+                    if stride != c_stride {
+                        layout.warnings.push(format!(
+                            "Member {name} has an array stride of {stride}, a C array would \
+                             have {c_stride}"
+                        ));
+                    }
+                }
+
+                if let (Some(stride), Some((c_stride, ..))) = (
+                    member.matrix_stride,
+                    self.matrix_vectors(
+                        LayoutRule::Scalar,
+                        self.innermost_element(member_type),
+                        row_major,
+                    ),
+                ) {
+                    if stride != c_stride {
+                        layout.warnings.push(format!(
+                            "Member {name} has a matrix stride of {stride}, a C array of \
+                             vectors would have {c_stride}"
+                        ));
+                    }
+                }
+            }
+
+            end = end.max(offset + member.size.unwrap_or_default());
+        }
+
+        if let Some(size) = layout.size.filter(|&size| size > end) {
+            layout.padding.push(Padding {
+                offset: end,
+                size: size - end,
+            });
+        }
+    }
+
+    fn c_array_stride(&self, array: Word, row_major: bool) -> Option<u32> {
+        let element = self.definitions.get(array)?.id_ref(0).ok()?;
+        let element = self.rule_layout(LayoutRule::Scalar, element, row_major)?;
+        Some(array_layout(LayoutRule::Scalar, element, 0).1)
+    }
+}
+
+fn vector_layout(rule: LayoutRule, component: u32, count: u32) -> RuleLayout {
+    let size = component * count;
+    let align = match (rule, count) {
+        (LayoutRule::Scalar, _) => component,
+        (_, 2) => 2 * component,
+        _ => 4 * component,
+    };
+    RuleLayout { align, size }
+}
+
+/// The layout and stride of an array. std140 rounds the alignment of the
+/// elements up to 16 bytes
+fn array_layout(rule: LayoutRule, element: RuleLayout, length: u32) -> (RuleLayout, u32) {
+    let align = match rule {
+        LayoutRule::Std140 => round_up(element.align, 16),
+        LayoutRule::Std430 | LayoutRule::Scalar => element.align,
+    };
+    let stride = round_up(element.size, align);
+    (
+        RuleLayout {
+            align,
+            size: stride * length,
+        },
+        stride,
+    )
+}
+
+fn round_up(value: u32, align: u32) -> u32 {
+    let align = align.max(1);
+    (value + align - 1) / align * align
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assembler::assemble, module_info::ModuleInfo};
+
+    /// Lays out the structs declared by `types`, which may use the scalar and
+    /// vector types `%float`, `%v2`, `%v3`, `%v4` and the constant `%uint_2`
+    fn layouts(decorations: &str, types: &str) -> Vec<StructLayout> {
+        let text = format!(
+            "OpCapability Shader
+             OpMemoryModel Logical GLSL450
+             {decorations}
+     %float = OpTypeFloat 32
+      %uint = OpTypeInt 32 0
+        %v2 = OpTypeVector %float 2
+        %v3 = OpTypeVector %float 3
+        %v4 = OpTypeVector %float 4
+    %uint_2 = OpConstant %uint 2
+             {types}"
+        );
+        let module = assemble(&text).unwrap();
+        ModuleInfo::create(&module).unwrap().structs
+    }
+
+    fn padding(layout: &StructLayout) -> Vec<(u32, u32)> {
+        layout
+            .padding
+            .iter()
+            .map(|padding| (padding.offset, padding.size))
+            .collect()
+    }
+
+    #[test]
+    fn vec3_followed_by_a_float() {
+        let layouts = layouts(
+            "OpMemberDecorate %S 0 Offset 0
+             OpMemberDecorate %S 1 Offset 12",
+            "%S = OpTypeStruct %v3 %float",
+        );
+        let layout = &layouts[0];
+        assert_eq!(
+            layout.rules,
+            [LayoutRule::Std140, LayoutRule::Std430, LayoutRule::Scalar]
+        );
+        assert_eq!(layout.size, Some(16));
+        assert_eq!(padding(layout), []);
+        assert!(layout.warnings.is_empty());
+    }
+
+    #[test]
+    fn vec3_followed_by_a_vec3() {
+        // Only scalar layout packs the second vec3 right after the first
+        let std = layouts(
+            "OpMemberDecorate %S 0 Offset 0
+             OpMemberDecorate %S 1 Offset 16",
+            "%S = OpTypeStruct %v3 %v3",
+        );
+        assert_eq!(std[0].rules, [LayoutRule::Std140, LayoutRule::Std430]);
+        assert_eq!(std[0].size, Some(32));
+        assert_eq!(padding(&std[0]), [(12, 4), (28, 4)]);
+
+        let scalar = layouts(
+            "OpMemberDecorate %S 0 Offset 0
+             OpMemberDecorate %S 1 Offset 12",
+            "%S = OpTypeStruct %v3 %v3",
+        );
+        assert_eq!(scalar[0].rules, [LayoutRule::Scalar]);
+        assert_eq!(scalar[0].size, Some(24));
+        assert_eq!(padding(&scalar[0]), []);
+    }
+
+    #[test]
+    fn size_is_rounded_up_to_the_alignment() {
+        let layouts = layouts(
+            "OpMemberDecorate %S 0 Offset 0
+             OpMemberDecorate %S 1 Offset 16",
+            "%S = OpTypeStruct %v4 %float",
+        );
+        let layout = &layouts[0];
+        assert_eq!(layout.size, Some(32));
+        assert_eq!(padding(layout), [(20, 12)]);
+    }
+
+    #[test]
+    fn small_structs_are_not_rounded_to_16() {
+        // As in a push constant block, where std140 would also match
+        let layouts = layouts(
+            "OpMemberDecorate %S 0 Offset 0
+             OpMemberDecorate %S 1 Offset 4",
+            "%S = OpTypeStruct %float %float",
+        );
+        assert_eq!(
+            layouts[0].rules,
+            [LayoutRule::Std140, LayoutRule::Std430, LayoutRule::Scalar]
+        );
+        assert_eq!(layouts[0].size, Some(8));
+        assert_eq!(padding(&layouts[0]), []);
+    }
+
+    #[test]
+    fn structs_in_std140_blocks_are_rounded_to_16() {
+        let layouts = layouts(
+            "OpMemberDecorate %Inner 0 Offset 0
+             OpMemberDecorate %Inner 1 Offset 4
+             OpMemberDecorate %Outer 0 Offset 0
+             OpMemberDecorate %Outer 1 Offset 16",
+            "%Inner = OpTypeStruct %float %float
+             %Outer = OpTypeStruct %Inner %float",
+        );
+        let (inner, outer) = (&layouts[0], &layouts[1]);
+        assert_eq!(outer.rules, [LayoutRule::Std140]);
+        assert_eq!(inner.size, Some(16));
+        assert_eq!(padding(inner), [(8, 8)]);
+        assert_eq!(outer.members[0].size, Some(16));
+        assert_eq!(padding(outer), [(20, 12)]);
+    }
+
+    #[test]
+    fn structs_in_std430_blocks_keep_their_size() {
+        let layouts = layouts(
+            "OpMemberDecorate %Inner 0 Offset 0
+             OpMemberDecorate %Inner 1 Offset 4
+             OpMemberDecorate %Outer 0 Offset 0
+             OpMemberDecorate %Outer 1 Offset 8",
+            "%Inner = OpTypeStruct %float %float
+             %Outer = OpTypeStruct %Inner %float",
+        );
+        let (inner, outer) = (&layouts[0], &layouts[1]);
+        assert_eq!(outer.rules, [LayoutRule::Std430, LayoutRule::Scalar]);
+        assert_eq!(inner.size, Some(8));
+        assert_eq!(outer.size, Some(12));
+        assert_eq!(padding(outer), []);
+    }
+
+    #[test]
+    fn std140_arrays_of_scalars() {
+        let layouts = layouts(
+            "OpDecorate %array ArrayStride 16
+             OpMemberDecorate %S 0 Offset 0
+             OpMemberDecorate %S 1 Offset 32",
+            "%array = OpTypeArray %float %uint_2
+                 %S = OpTypeStruct %array %float",
+        );
+        let layout = &layouts[0];
+        assert_eq!(layout.rules, [LayoutRule::Std140]);
+        assert_eq!(layout.members[0].size, Some(32));
+        assert_eq!(layout.size, Some(48));
+        assert_eq!(padding(layout), [(36, 12)]);
+        assert_eq!(
+            layout.warnings,
+            ["Member 0 has an array stride of 16, a C array would have 4"]
+        );
+    }
+
+    #[test]
+    fn arrays_of_structs() {
+        let layouts = layouts(
+            "OpMemberDecorate %Inner 0 Offset 0
+             OpMemberDecorate %Inner 1 Offset 16
+             OpDecorate %array ArrayStride 32
+             OpMemberDecorate %Outer 0 Offset 0
+             OpMemberDecorate %Outer 1 Offset 64",
+            "%Inner = OpTypeStruct %v4 %float
+             %array = OpTypeArray %Inner %uint_2
+             %Outer = OpTypeStruct %array %float",
+        );
+        let (inner, outer) = (&layouts[0], &layouts[1]);
+
+        assert_eq!(inner.size, Some(32));
+        assert_eq!(outer.rules, [LayoutRule::Std140, LayoutRule::Std430]);
+        assert_eq!(outer.members[0].size, Some(64));
+        assert_eq!(outer.size, Some(80));
+        assert_eq!(padding(outer), [(68, 12)]);
+    }
+
+    #[test]
+    fn arrays_of_structs_with_std430_stride() {
+        // std140 would round the stride of the 8 byte struct up to 16
+        let layouts = layouts(
+            "OpMemberDecorate %Inner 0 Offset 0
+             OpDecorate %array ArrayStride 8
+             OpMemberDecorate %Outer 0 Offset 0
+             OpMemberDecorate %Outer 1 Offset 16",
+            "%Inner = OpTypeStruct %v2
+             %array = OpTypeArray %Inner %uint_2
+             %Outer = OpTypeStruct %array %float",
+        );
+        let (inner, outer) = (&layouts[0], &layouts[1]);
+        assert_eq!(inner.size, Some(8));
+        assert_eq!(padding(inner), []);
+        assert_eq!(outer.rules, [LayoutRule::Std430, LayoutRule::Scalar]);
+        assert_eq!(outer.members[0].size, Some(16));
+        assert_eq!(outer.size, Some(24));
+        assert_eq!(padding(outer), [(20, 4)]);
+    }
+
+    #[test]
+    fn scalar_layout() {
+        let layouts = layouts(
+            "OpMemberDecorate %S 0 Offset 0
+             OpMemberDecorate %S 1 Offset 4
+             OpMemberDecorate %S 2 Offset 16",
+            "%S = OpTypeStruct %float %v3 %float",
+        );
+        let layout = &layouts[0];
+        assert_eq!(layout.rules, [LayoutRule::Scalar]);
+        assert_eq!(layout.size, Some(20));
+        assert_eq!(padding(layout), []);
+        assert!(layout.warnings.is_empty());
+    }
+
+    #[test]
+    fn no_matching_rule() {
+        let layouts = layouts(
+            "OpMemberDecorate %S 0 Offset 0
+             OpMemberDecorate %S 1 Offset 8",
+            "%S = OpTypeStruct %float %float",
+        );
+        let layout = &layouts[0];
+        assert_eq!(layout.rules, []);
+        // Aligned like a C struct
+        assert_eq!(layout.size, Some(12));
+        assert_eq!(padding(layout), [(4, 4)]);
+        assert_eq!(
+            layout.warnings,
+            [
+                "The layout matches none of std140, std430 or scalar",
+                "Member 1 is at offset 8, a C struct would place it at 4"
+            ]
+        );
+    }
+
+    #[test]
+    fn runtime_arrays_have_no_size() {
+        let layouts = layouts(
+            "OpDecorate %array ArrayStride 4
+             OpMemberDecorate %S 0 Offset 0
+             OpMemberDecorate %S 1 Offset 4",
+            "%array = OpTypeRuntimeArray %float
+                 %S = OpTypeStruct %float %array",
+        );
+        assert_eq!(layouts[0].size, None);
+        assert_eq!(layouts[0].members[1].size, None);
+    }
+}
@@ -0,0 +1,425 @@
+//! Compiles shaders with shaderc and annotates the resulting SPIR-V with
+//! friendly names and source line mappings.
+
+pub mod annotated_disassembly;
+pub mod assembler;
+pub mod compilation;
+pub mod diagnostics;
+pub mod diff;
+pub mod host_structs;
+pub mod includes;
+pub mod layout;
+pub mod module_info;
+pub mod operands;
+pub mod options;
+pub mod pass_pipeline;
+pub mod reflection;
+pub mod specialization;
+pub mod spirv_binary;
+pub mod validation;
+
+use crate::{
+    annotated_disassembly::AnnotatedDisassembly,
+    diagnostics::Diagnostics,
+    includes::IncludeResolver,
+    options::{
+        CompileShaderOptions, LoadOptions, ShaderKind, SourceLanguage, TargetEnv, TargetEnvVersion,
+    },
+};
+use lazy_static::lazy_static;
+use rspirv::{
+    binary::{Assemble, Disassemble},
+    dr::{load_words, Module},
+};
+use shaderc::{parse_version_profile, CompileOptions, Compiler, EnvVersion};
+use std::{error::Error, fmt};
+
+lazy_static! {
+    static ref SHADERC: Compiler = Compiler::new().unwrap();
+}
+
+pub struct CompiledShader {
+    pub module: Module,
+    pub assembly: AnnotatedDisassembly,
+    pub warning: String,
+    pub diagnostics: Diagnostics,
+    pub target_env_version: TargetEnvVersion,
+    /// The version that was actually emitted, which is the target
+    /// environment's default if none was requested
+    pub spirv_version: (u8, u8),
+}
+
+/// A module that was loaded from an existing binary or assembled from text
+/// rather than compiled
+pub struct LoadedModule {
+    pub module: Module,
+    pub assembly: AnnotatedDisassembly,
+    pub spirv_version: (u8, u8),
+}
+
+#[derive(Debug)]
+pub enum CompileError {
+    /// The options cannot be applied, e.g. an OpenGL target with a Vulkan
+    /// version
+    InvalidOptions(String),
+    /// The input is not a SPIR-V binary
+    InvalidSpirv(String),
+    /// SPIR-V assembly text could not be assembled
+    Assembly(Diagnostics),
+    /// The optimizer could not apply the specialization constant overrides
+    Specialization(String),
+    /// shaderc rejected the source
+    Compilation {
+        message: String,
+        diagnostics: Diagnostics,
+    },
+    /// Compilation succeeded, but the resulting module could not be annotated
+    Analysis {
+        error: eyre::Report,
+        /// Plain disassembly, if the module could at least be parsed
+        disassembly: Option<String>,
+        warning: String,
+        diagnostics: Diagnostics,
+    },
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidOptions(message)
+            | Self::InvalidSpirv(message)
+            | Self::Specialization(message) => write!(f, "{message}"),
+            Self::Compilation { message, .. } => write!(f, "{message}"),
+            Self::Assembly(diagnostics) => {
+                for diagnostic in diagnostics.messages.iter() {
+                    if let (Some(line), Some(column)) = (diagnostic.line, diagnostic.column) {
+                        write!(f, "{line}:{column}: ")?;
+                    }
+                    writeln!(f, "{}", diagnostic.message)?;
+                }
+                Ok(())
+            },
+            Self::Analysis { error, .. } => write!(f, "{error:#}"),
+        }
+    }
+}
+
+impl Error for CompileError {}
+
+impl CompileError {
+    pub fn diagnostics(&self) -> Diagnostics {
+        match self {
+            Self::InvalidOptions(message)
+            | Self::InvalidSpirv(message)
+            | Self::Specialization(message) => Diagnostics::error(message),
+            Self::Compilation { diagnostics, .. }
+            | Self::Assembly(diagnostics)
+            | Self::Analysis { diagnostics, .. } => diagnostics.clone(),
+        }
+    }
+}
+
+pub fn compile_shader(
+    source: &str,
+    shader_kind: ShaderKind,
+    options: &CompileShaderOptions,
+) -> Result<CompiledShader, CompileError> {
+    let compiler: &Compiler = &SHADERC;
+
+    let mut compile_options = CompileOptions::new()
+        .ok_or_else(|| CompileError::InvalidOptions("Failed to create compile options".into()))?;
+
+    let is_hlsl = options.source_language == SourceLanguage::Hlsl;
+    if is_hlsl {
+        compile_options.set_source_language(shaderc::SourceLanguage::HLSL);
+    }
+
+    if let Some(version_profile) = options.forced_version_profile.as_ref() {
+        let (version, profile) = parse_version_profile(version_profile).ok_or_else(|| {
+            CompileError::InvalidOptions(format!(
+                "Invalid GLSL version and profile: {}",
+                version_profile
+            ))
+        })?;
+        compile_options.set_forced_version_profile(version, profile);
+    }
+
+    for (kind, base) in options.binding_base.iter() {
+        compile_options.set_binding_base((*kind).into(), *base);
+    }
+
+    // HLSL register shifts are applied after the generic binding bases, so they
+    // take precedence
+    if is_hlsl {
+        let hlsl = &options.hlsl;
+
+        for (register, shift) in hlsl.register_shifts.iter() {
+            compile_options.set_binding_base((*register).into(), *shift);
+        }
+
+        for binding in hlsl.register_bindings.iter() {
+            compile_options.set_hlsl_register_set_and_binding(
+                &binding.register,
+                &binding.set.to_string(),
+                &binding.binding.to_string(),
+            );
+        }
+
+        compile_options.set_hlsl_functionality1(hlsl.functionality1);
+        compile_options.set_hlsl_offsets(hlsl.offsets);
+        compile_options.set_hlsl_io_mapping(hlsl.io_mapping);
+    }
+
+    let target_env_version = options
+        .target_env_version
+        .unwrap_or_else(|| options.target_env.default_version());
+    let env_version = match (options.target_env, target_env_version) {
+        (TargetEnv::Vulkan, TargetEnvVersion::V1_0) => EnvVersion::Vulkan1_0,
+        (TargetEnv::Vulkan, TargetEnvVersion::V1_1) => EnvVersion::Vulkan1_1,
+        (TargetEnv::Vulkan, TargetEnvVersion::V1_2) => EnvVersion::Vulkan1_2,
+        (TargetEnv::Vulkan, TargetEnvVersion::V1_3) => EnvVersion::Vulkan1_3,
+        (TargetEnv::OpenGL, TargetEnvVersion::V4_5) => EnvVersion::OpenGL4_5,
+        (target_env, version) => {
+            return Err(CompileError::InvalidOptions(format!(
+                "Unknown {:?} version: {}",
+                target_env, version
+            )))
+        },
+    };
+    let shaderc_target_env = match options.target_env {
+        TargetEnv::Vulkan => shaderc::TargetEnv::Vulkan,
+        TargetEnv::OpenGL => shaderc::TargetEnv::OpenGL,
+    };
+    compile_options.set_target_env(shaderc_target_env, env_version as u32);
+
+    // OpenGL has no descriptor sets, so GLSL written for it usually relies on
+    // automatic locations and bindings
+    let is_opengl = options.target_env == TargetEnv::OpenGL;
+    compile_options.set_auto_map_locations(options.auto_map_locations.unwrap_or(is_opengl));
+    compile_options.set_auto_bind_uniforms(options.auto_bind_uniforms.unwrap_or(is_opengl));
+    compile_options.set_auto_combined_image_sampler(options.auto_combined_image_sampler);
+
+    compile_options.set_invert_y(options.invert_y);
+    compile_options.set_nan_clamp(options.nan_clamp);
+
+    if let Some(spirv_version) = options.spirv_version {
+        compile_options.set_target_spirv(spirv_version.into());
+    }
+
+    compile_options.set_optimization_level(options.optimization.into());
+
+    for (name, value) in options.macros.iter() {
+        compile_options.add_macro_definition(name, value.as_ref().map(|s| s.as_str()));
+    }
+
+    let include_resolver = IncludeResolver::new(
+        &options.sources,
+        &options.include_directories,
+        options.max_include_depth,
+    );
+    compile_options.set_include_callback(move |requested, include_type, requesting, depth| {
+        include_resolver.resolve(requested, include_type, requesting, depth)
+    });
+
+    // Debug info is kept when optimizing so that OpLine survives wherever the
+    // optimizer preserves it
+    compile_options.set_generate_debug_info();
+
+    let file_name = options
+        .file_name
+        .as_ref()
+        .map(|s| s.as_str())
+        .unwrap_or(if is_hlsl {
+            "shader.hlsl"
+        } else {
+            "shader.glsl"
+        });
+
+    let result = compiler.compile_into_spirv(
+        source,
+        shader_kind.into(),
+        file_name,
+        options
+            .entry_point
+            .as_ref()
+            .map(|s| s.as_str())
+            .filter(|_| is_hlsl)
+            .unwrap_or("main"),
+        Some(&compile_options),
+    );
+
+    let artifact = match result {
+        Ok(artifact) => artifact,
+        Err(shaderc::Error::CompilationError(error_count, message)) => {
+            return Err(CompileError::Compilation {
+                diagnostics: Diagnostics {
+                    error_count,
+                    ..Diagnostics::parse(&message)
+                },
+                message,
+            })
+        },
+        Err(e) => {
+            let message = e.to_string();
+            return Err(CompileError::Compilation {
+                diagnostics: Diagnostics::error(&message),
+                message,
+            });
+        },
+    };
+
+    let warning = artifact.get_warning_messages();
+    let diagnostics = Diagnostics {
+        warning_count: artifact.get_num_warnings(),
+        ..Diagnostics::parse(&warning)
+    };
+
+    let (module, assembly) = annotate(
+        artifact.as_binary(),
+        options.limit_result_name_length,
+        &warning,
+        &diagnostics,
+    )?;
+
+    let spirv_version = spirv_version(&module);
+
+    let validator_env = validation::validator_env(
+        Some((options.target_env, target_env_version)),
+        spirv_version,
+    )?;
+
+    // Specializing rewrites the module, so the result is annotated again
+    let specialized = if options.specialization.is_empty() {
+        None
+    } else {
+        Some(specialization::specialize(
+            &module,
+            &options.specialization,
+            options.optimization,
+            validator_env.env(),
+        )?)
+    };
+    let (module, mut assembly) = match &specialized {
+        Some(words) => annotate(
+            words,
+            options.limit_result_name_length,
+            &warning,
+            &diagnostics,
+        )?,
+        None => (module, assembly),
+    };
+    let words = specialized
+        .as_deref()
+        .unwrap_or_else(|| artifact.as_binary());
+    assembly.attach_validation(validation::validate(words, &validator_env));
+
+    Ok(CompiledShader {
+        module,
+        assembly,
+        warning,
+        diagnostics,
+        target_env_version,
+        spirv_version,
+    })
+}
+
+/// Annotates an existing SPIR-V binary, in either byte order.
+///
+/// Source line annotations are only available if the module was built with
+/// debug information.
+pub fn disassemble_spirv(
+    bytes: &[u8],
+    options: &LoadOptions,
+) -> Result<LoadedModule, CompileError> {
+    let words = spirv_binary::words_from_bytes(bytes)
+        .map_err(|e| CompileError::InvalidSpirv(e.to_string()))?;
+
+    let module = load(&words, "", &Diagnostics::default())?;
+    loaded_module(module, &words, options)
+}
+
+/// Assembles SPIR-V text in either the friendly syntax of the annotated
+/// disassembly or standard `spirv-as` syntax, and annotates the result.
+///
+/// The SPIR-V version is taken from a `; Version: 1.x` header comment, if
+/// present.
+pub fn assemble_spirv(text: &str, options: &LoadOptions) -> Result<LoadedModule, CompileError> {
+    let module = assembler::assemble(text).map_err(CompileError::Assembly)?;
+    let words = module.assemble();
+    loaded_module(module, &words, options)
+}
+
+fn loaded_module(
+    module: Module,
+    words: &[u32],
+    options: &LoadOptions,
+) -> Result<LoadedModule, CompileError> {
+    let spirv_version = spirv_version(&module);
+    let target = options.target_env.map(|target_env| {
+        let version = options
+            .target_env_version
+            .unwrap_or_else(|| target_env.default_version());
+        (target_env, version)
+    });
+    let validator_env = validation::validator_env(target, spirv_version)?;
+
+    let mut assembly = annotate_module(
+        &module,
+        options.limit_result_name_length,
+        "",
+        &Diagnostics::default(),
+    )?;
+    assembly.attach_validation(validation::validate(words, &validator_env));
+
+    Ok(LoadedModule {
+        spirv_version,
+        module,
+        assembly,
+    })
+}
+
+/// Loads and annotates a module. Compiler output is passed through so it can be
+/// reported alongside analysis errors
+fn annotate(
+    words: &[u32],
+    limit_result_name_length: Option<usize>,
+    warning: &str,
+    diagnostics: &Diagnostics,
+) -> Result<(Module, AnnotatedDisassembly), CompileError> {
+    let module = load(words, warning, diagnostics)?;
+    let assembly = annotate_module(&module, limit_result_name_length, warning, diagnostics)?;
+    Ok((module, assembly))
+}
+
+fn load(words: &[u32], warning: &str, diagnostics: &Diagnostics) -> Result<Module, CompileError> {
+    load_words(words).map_err(|e| CompileError::Analysis {
+        error: eyre::eyre!("Failed to parse SPIR-V: {e}"),
+        disassembly: None,
+        warning: warning.to_string(),
+        diagnostics: diagnostics.clone(),
+    })
+}
+
+fn annotate_module(
+    module: &Module,
+    limit_result_name_length: Option<usize>,
+    warning: &str,
+    diagnostics: &Diagnostics,
+) -> Result<AnnotatedDisassembly, CompileError> {
+    AnnotatedDisassembly::create(module, limit_result_name_length).map_err(|e| {
+        CompileError::Analysis {
+            error: e.wrap_err("Failed to annotate SPIR-V"),
+            disassembly: Some(module.disassemble()),
+            warning: warning.to_string(),
+            diagnostics: diagnostics.clone(),
+        }
+    })
+}
+
+fn spirv_version(module: &Module) -> (u8, u8) {
+    module
+        .header
+        .as_ref()
+        .map(|header| header.version())
+        .unwrap_or_default()
+}
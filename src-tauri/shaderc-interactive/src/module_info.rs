@@ -0,0 +1,1238 @@
+use crate::{
+    layout::{self, StructLayout},
+    operands::InstructionOperands,
+    reflection::{self, Decorations, Definitions, EntryPoint, Resource, SpecConstant},
+};
+use bitflags::Flags;
+use eyre::{eyre, Result};
+use itertools::Itertools;
+use rspirv::{
+    binary::Disassemble,
+    dr::{Instruction, Module, Operand},
+};
+use serde::{Deserialize, Serialize};
+use spirv::{AccessQualifier, Decoration, Dim, ImageFormat, Op, StorageClass, Word};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ModuleInfo {
+    pub strings: HashMap<Word, String>,
+    pub names: HashMap<Word, String>,
+    /// Descriptor set resources, in declaration order
+    pub resources: Vec<Resource>,
+    /// Stage inputs and outputs of every entry point
+    pub entry_points: Vec<EntryPoint>,
+    /// Every struct type with its explicit layout, if it has one
+    pub structs: Vec<StructLayout>,
+    /// Specialization constants, in declaration order
+    pub spec_constants: Vec<SpecConstant>,
+    /// The decoded literal of every scalar constant, shown in place of its bit
+    /// pattern
+    #[serde(skip)]
+    literals: HashMap<Word, String>,
+}
+
+impl ModuleInfo {
+    pub fn create(module: &Module) -> Result<Self> {
+        let mut strings = HashMap::new();
+        let mut names = HashMap::new();
+        let mut scalar_types = HashMap::<Word, ScalarType>::new();
+        let mut vector_types = HashMap::<Word, (u32, String)>::new();
+        let mut array_types = HashSet::<Word>::new();
+        let mut constants_int32 = HashMap::<Word, u32>::new();
+        // Constants by value without their type, as they appear in composites
+        let mut constant_values = HashMap::<Word, String>::new();
+        let mut spec_ids = HashMap::<Word, u32>::new();
+        let mut spec_defaults = HashMap::<Word, String>::new();
+        let mut literals = HashMap::new();
+
+        for instruction in module.all_inst_iter() {
+            macro_rules! resolve_name {
+                ($id:expr) => {{
+                    names
+                        .get(&$id)
+                        .cloned()
+                        .unwrap_or_else(|| ($id).to_string())
+                }};
+            }
+
+            macro_rules! resolve_value {
+                ($id:expr) => {{
+                    constant_values
+                        .get(&$id)
+                        .cloned()
+                        .unwrap_or_else(|| resolve_name!($id))
+                }};
+            }
+
+            match instruction.class.opcode {
+                Op::String => {
+                    let id = instruction.result_id()?;
+                    let value = instruction.literal_string(0)?;
+                    strings.insert(id, value.to_string());
+                },
+                Op::Name => {
+                    let id = instruction.id_ref(0)?;
+                    let name = instruction.literal_string(1)?;
+                    names.insert(id, name.to_string());
+                },
+                Op::Decorate if instruction.decoration(1)? == Decoration::SpecId => {
+                    spec_ids.insert(instruction.id_ref(0)?, instruction.literal_int32(2)?);
+                },
+
+                // Types
+                Op::TypeVoid => {
+                    names.insert(instruction.result_id()?, "void".to_string());
+                },
+                Op::TypeBool => {
+                    names.insert(instruction.result_id()?, "bool".to_string());
+                },
+                Op::TypeAccelerationStructureNV => {
+                    names.insert(
+                        instruction.result_id()?,
+                        "AccelerationStructure".to_string(),
+                    );
+                },
+                Op::TypeRayQueryKHR => {
+                    names.insert(instruction.result_id()?, "rayQuery".to_string());
+                },
+                Op::TypeSampler => {
+                    names.insert(instruction.result_id()?, "sampler".to_string());
+                },
+                Op::TypeImage => {
+                    let sampled_type = instruction.id_ref(0)?;
+                    let name = image_type_name(instruction, resolve_name!(sampled_type))?;
+                    names.insert(instruction.result_id()?, name);
+                },
+                Op::TypeSampledImage => {
+                    let image = instruction.id_ref(0)?;
+                    // Combined images are always sampled, so that goes without saying
+                    let image_name = resolve_name!(image).replacen(",sampled", "", 1);
+                    names.insert(instruction.result_id()?, format!("combined<{image_name}>"));
+                },
+                Op::TypeStruct => {
+                    let member_type_names = (0..instruction.operands.len())
+                        .map(|index| {
+                            let id = instruction.id_ref(index)?;
+                            Ok(resolve_name!(id))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+
+                    // Structs are usually named by OpName, which comes first
+                    names
+                        .entry(instruction.result_id()?)
+                        .or_insert_with(|| format!("struct{{{}}}", member_type_names.join(";")));
+                },
+                Op::TypeOpaque => {
+                    let name = instruction.literal_string(0)?;
+                    names.insert(instruction.result_id()?, format!("opaque<{name}>"));
+                },
+
+                // OpenCL kernel types
+                Op::TypeEvent => {
+                    names.insert(instruction.result_id()?, "event".to_string());
+                },
+                Op::TypeDeviceEvent => {
+                    names.insert(instruction.result_id()?, "deviceEvent".to_string());
+                },
+                Op::TypeReserveId => {
+                    names.insert(instruction.result_id()?, "reserveId".to_string());
+                },
+                Op::TypeQueue => {
+                    names.insert(instruction.result_id()?, "queue".to_string());
+                },
+                Op::TypePipe => {
+                    let access = access_qualifier_name(instruction.access_qualifier(0)?);
+                    names.insert(instruction.result_id()?, format!("pipe<{access}>"));
+                },
+                Op::TypePipeStorage => {
+                    names.insert(instruction.result_id()?, "pipeStorage".to_string());
+                },
+                Op::TypeNamedBarrier => {
+                    names.insert(instruction.result_id()?, "namedBarrier".to_string());
+                },
+                Op::TypeInt => {
+                    let bits = instruction.literal_int32(0)?;
+                    let signed = instruction.literal_int32(1)? == 1;
+                    scalar_types.insert(instruction.result_id()?, ScalarType::Int { bits, signed });
+                    names.insert(
+                        instruction.result_id()?,
+                        format!("{}{}", if signed { "i" } else { "u" }, bits),
+                    );
+                },
+                Op::TypeFloat => {
+                    let bits = instruction.literal_int32(0)?;
+                    scalar_types.insert(instruction.result_id()?, ScalarType::Float { bits });
+                    names.insert(instruction.result_id()?, format!("f{}", bits));
+                },
+                Op::TypeFunction => {
+                    let function_return_type = instruction.id_ref(0)?;
+                    let function_return_type_name = resolve_name!(function_return_type);
+
+                    let argument_type_names = (1..instruction.operands.len())
+                        .map(|index| {
+                            let id = instruction.id_ref(index)?;
+                            Ok(resolve_name!(id))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+
+                    names.insert(
+                        instruction.result_id()?,
+                        format!(
+                            "fn({}){}",
+                            argument_type_names.join(";"),
+                            function_return_type_name
+                        ),
+                    );
+                },
+                Op::TypePointer => {
+                    let ptr_type = instruction.id_ref(1)?;
+                    let ptr_type_name = resolve_name!(ptr_type);
+
+                    let storage_class = instruction.storage_class(0)?;
+
+                    names.insert(
+                        instruction.result_id()?,
+                        format!("p{}*{}", storage_class_name(storage_class), ptr_type_name,),
+                    );
+                },
+                // Pointers to structs that contain them, like linked lists in
+                // physical storage buffers. The pointee is not declared yet, so
+                // it is named by its OpName or id until OpTypePointer follows
+                Op::TypeForwardPointer => {
+                    let pointer = instruction.id_ref(0)?;
+                    let storage_class = instruction.storage_class(1)?;
+
+                    let pointee = module
+                        .types_global_values
+                        .iter()
+                        .find(|definition| {
+                            definition.class.opcode == Op::TypePointer
+                                && definition.result_id == Some(pointer)
+                        })
+                        .map(|definition| definition.id_ref(1))
+                        .transpose()?;
+                    let pointee_name = match pointee {
+                        Some(pointee) => resolve_name!(pointee),
+                        None => "?".to_string(),
+                    };
+
+                    names.insert(
+                        pointer,
+                        format!("p{}*{}", storage_class_name(storage_class), pointee_name),
+                    );
+                },
+                // Vector, Matrix and Array types
+                Op::TypeVector => {
+                    let component = instruction.id_ref(0)?;
+                    let component_name = resolve_name!(component);
+
+                    let count = instruction.literal_int32(1)?;
+
+                    vector_types.insert(instruction.result_id()?, (count, component_name.clone()));
+
+                    names.insert(
+                        instruction.result_id()?,
+                        format!("{}x{}", count, component_name),
+                    );
+                },
+                Op::TypeMatrix => {
+                    let component = instruction.id_ref(0)?;
+
+                    let (rows, component_name) = vector_types.get(&component).ok_or_else(|| {
+                        eyre!("OpTypeMatrix column type {component} is not a vector")
+                    })?;
+                    let columns = instruction.literal_int32(1)?;
+
+                    names.insert(
+                        instruction.result_id()?,
+                        format!("{}x{}x{}", rows, columns, component_name),
+                    );
+                },
+                Op::TypeArray => {
+                    let component = instruction.id_ref(0)?;
+                    let component_name = resolve_name!(component);
+
+                    // The length may be a specialization constant or a constant
+                    // that does not fit into 32 bits, which we show by name
+                    let count = instruction.id_ref(1)?;
+                    let constant_count = constants_int32
+                        .get(&count)
+                        .map(|count| count.to_string())
+                        .unwrap_or_else(|| resolve_name!(count));
+
+                    array_types.insert(instruction.result_id()?);
+                    names.insert(
+                        instruction.result_id()?,
+                        format!("[{};{}]", component_name, constant_count),
+                    );
+                },
+                Op::TypeRuntimeArray => {
+                    let component = instruction.id_ref(0)?;
+                    let component_name = resolve_name!(component);
+
+                    array_types.insert(instruction.result_id()?);
+                    names.insert(instruction.result_id()?, format!("[{}]", component_name,));
+                },
+
+                // Constants
+                Op::Constant => {
+                    let id = instruction.result_id()?;
+                    let result_type = instruction.result_type()?;
+                    let operand = instruction.operand(0)?;
+
+                    if let Operand::LiteralBit32(v) = operand {
+                        constants_int32.insert(id, *v);
+                    }
+
+                    let value = scalar_value(operand, scalar_types.get(&result_type).copied());
+                    names.insert(id, format!("{}{}", value, resolve_name!(result_type)));
+                    literals.insert(id, value.clone());
+                    constant_values.insert(id, value);
+                },
+                Op::ConstantTrue | Op::ConstantFalse | Op::ConstantNull => {
+                    let value = match instruction.class.opcode {
+                        Op::ConstantTrue => "true",
+                        Op::ConstantFalse => "false",
+                        _ => "null",
+                    };
+                    names.insert(instruction.result_id()?, value.to_string());
+                    constant_values.insert(instruction.result_id()?, value.to_string());
+                },
+                Op::ConstantComposite => {
+                    let result_type = instruction.result_type()?;
+                    let components = (0..instruction.operands.len())
+                        .map(|index| {
+                            let id = instruction.id_ref(index)?;
+                            Ok(resolve_value!(id))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+
+                    let value = composite_value(
+                        &resolve_name!(result_type),
+                        vector_types.get(&result_type),
+                        array_types.contains(&result_type),
+                        &components,
+                    );
+                    names.insert(instruction.result_id()?, value.clone());
+                    constant_values.insert(instruction.result_id()?, value);
+                },
+
+                // Specialization constants keep their OpName, if they have one
+                Op::SpecConstant | Op::SpecConstantTrue | Op::SpecConstantFalse => {
+                    let id = instruction.result_id()?;
+                    let default = match instruction.class.opcode {
+                        Op::SpecConstantTrue => "true".to_string(),
+                        Op::SpecConstantFalse => "false".to_string(),
+                        _ => {
+                            let default = scalar_value(
+                                instruction.operand(0)?,
+                                scalar_types.get(&instruction.result_type()?).copied(),
+                            );
+                            literals.insert(id, default.clone());
+                            default
+                        },
+                    };
+
+                    let details = match spec_ids.get(&id) {
+                        Some(spec_id) => format!("id={spec_id},default={default}"),
+                        None => format!("default={default}"),
+                    };
+                    let name = format!("{}({details})", names.get(&id).map_or("spec", |name| name));
+                    names.insert(id, name);
+                    spec_defaults.insert(id, default);
+                },
+                Op::SpecConstantComposite => {
+                    let result_type = instruction.result_type()?;
+                    let components = (0..instruction.operands.len())
+                        .map(|index| {
+                            let id = instruction.id_ref(index)?;
+                            Ok(resolve_value!(id))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+
+                    let value = composite_value(
+                        &resolve_name!(result_type),
+                        vector_types.get(&result_type),
+                        array_types.contains(&result_type),
+                        &components,
+                    );
+                    names
+                        .entry(instruction.result_id()?)
+                        .or_insert_with(|| value.clone());
+                    spec_defaults.insert(instruction.result_id()?, value);
+                },
+                Op::SpecConstantOp => {
+                    let operation = instruction.operand(0)?;
+                    let arguments = instruction.operands[1..]
+                        .iter()
+                        .map(|operand| match operand {
+                            Operand::IdRef(id) => resolve_value!(*id),
+                            _ => operand.to_string(),
+                        })
+                        .join(",");
+
+                    let expression = format!("{operation}({arguments})");
+                    names
+                        .entry(instruction.result_id()?)
+                        .or_insert_with(|| expression.clone());
+                    spec_defaults.insert(instruction.result_id()?, expression);
+                },
+
+                _ => (),
+            }
+        }
+
+        let names = unique_names(names);
+
+        let definitions = Definitions::new(module, &names);
+        let decorations = Decorations::collect(module)?;
+        let member_names = reflection::member_names(module)?;
+        let resources = reflection::resources(module, &definitions, &decorations)?;
+        let entry_points =
+            reflection::entry_points(module, &definitions, &member_names, &decorations)?;
+        let structs = layout::struct_layouts(module, &definitions, &member_names, &decorations)?;
+        let spec_constants =
+            reflection::spec_constants(module, &definitions, &decorations, &spec_defaults)?;
+
+        Ok(Self {
+            strings,
+            names,
+            resources,
+            entry_points,
+            structs,
+            spec_constants,
+            literals,
+        })
+    }
+
+    pub fn operand_name(&self, operand: Word) -> String {
+        self.names
+            .get(&operand)
+            .map(|name| format!("%{name}"))
+            .unwrap_or_else(|| format!("%{operand}"))
+    }
+
+    pub fn disassemble_instruction(&self, instruction: &Instruction) -> InstructionDisassembly {
+        let result = instruction
+            .result_id
+            .map(|result| self.operand_name(result));
+        let result_type = instruction
+            .result_type
+            .map(|result| self.operand_name(result));
+
+        let name = format!("Op{}", instruction.class.opname);
+        let literal = instruction
+            .result_id
+            .and_then(|result| self.literals.get(&result));
+        let operands = match (instruction.class.opcode, literal) {
+            (Op::Constant | Op::SpecConstant, Some(literal)) => vec![literal.clone()],
+            _ => instruction
+                .operands
+                .iter()
+                .map(|operand| match operand {
+                    Operand::IdRef(id) => self.operand_name(*id),
+                    _ => operand_text(operand),
+                })
+                .collect_vec(),
+        };
+
+        InstructionDisassembly {
+            result,
+            result_type,
+            name,
+            operands,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ScalarType {
+    Int { bits: u32, signed: bool },
+    Float { bits: u32 },
+}
+
+/// Decodes the literal of a scalar constant according to its type, which is
+/// stored as its bit pattern. Half floats take the low bits of a word
+fn scalar_value(operand: &Operand, scalar_type: Option<ScalarType>) -> String {
+    match (operand, scalar_type) {
+        // Integers without bits are invalid, but may come from any binary
+        (Operand::LiteralBit32(v), Some(ScalarType::Int { bits, signed: true })) if bits > 0 => {
+            // Narrower types are sign extended to 32 bits, but only if the
+            // producer followed the rules
+            let shift = 32 - bits.min(32);
+            (((*v << shift) as i32) >> shift).to_string()
+        },
+        (Operand::LiteralBit64(v), Some(ScalarType::Int { signed: true, .. })) => {
+            (*v as i64).to_string()
+        },
+        (Operand::LiteralBit32(v), Some(ScalarType::Float { bits: 16 })) => {
+            format!("{:?}", f16_value(*v as u16))
+        },
+        (Operand::LiteralBit32(v), Some(ScalarType::Float { .. })) => {
+            format!("{:?}", f32::from_bits(*v))
+        },
+        (Operand::LiteralBit64(v), Some(ScalarType::Float { .. })) => {
+            format!("{:?}", f64::from_bits(*v))
+        },
+        (operand, _) => operand.to_string(),
+    }
+}
+
+/// rspirv writes most flags with their grammar names, e.g. `Inline|DontInline`,
+/// and the others as `Debug`. Those are written with their constant names
+/// instead, which the assembler accepts as well
+fn operand_text(operand: &Operand) -> String {
+    match operand {
+        Operand::RayFlags(flags) => flag_names(*flags),
+        Operand::FragmentShadingRate(flags) => flag_names(*flags),
+        Operand::CooperativeMatrixOperands(flags) => flag_names(*flags),
+        _ => operand.disassemble(),
+    }
+}
+
+fn flag_names<T: Flags<Bits = u32>>(flags: T) -> String {
+    match flags.iter_names().map(|(name, _)| name).join("|") {
+        names if names.is_empty() => flags.bits().to_string(),
+        names => names,
+    }
+}
+
+/// Converts the bits of a half float. Every half is exactly representable
+fn f16_value(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let mantissa = f32::from(bits & 0x3ff);
+
+    sign * match exponent {
+        0 => mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+/// Renders a composite like GLSL, e.g. `vec3(0.0,1.0,0.0)` or `[1,2]`. Types
+/// without a GLSL vector name are written by their friendly name
+fn composite_value(
+    type_name: &str,
+    vector: Option<&(u32, String)>,
+    is_array: bool,
+    components: &[String],
+) -> String {
+    let components = components.join(",");
+
+    let prefix = vector.and_then(|(count, component)| {
+        let prefix = match component.as_str() {
+            "f32" => "vec",
+            "f64" => "dvec",
+            "i32" => "ivec",
+            "u32" => "uvec",
+            "bool" => "bvec",
+            _ => return None,
+        };
+        Some(format!("{prefix}{count}"))
+    });
+
+    match prefix {
+        Some(prefix) => format!("{prefix}({components})"),
+        None if is_array => format!("[{components}]"),
+        None => format!("{type_name}({components})"),
+    }
+}
+
+/// Appends the id to every name that is shared by several ids.
+///
+/// A suffixed name can still be taken by another id, e.g. `x_5` next to two
+/// `x`. Those get the id appended again, going through the shared names in id
+/// order so the result is the same on every run.
+fn unique_names(mut names: HashMap<Word, String>) -> HashMap<Word, String> {
+    let mut counts = HashMap::<&str, usize>::with_capacity(names.len());
+    for name in names.values() {
+        *counts.entry(name).or_default() += 1;
+    }
+
+    let mut taken = HashSet::with_capacity(names.len());
+    let mut shared = Vec::new();
+    for (id, name) in names.iter() {
+        if counts[name.as_str()] > 1 {
+            shared.push((*id, format!("{name}_{id}")));
+        } else {
+            taken.insert(name.clone());
+        }
+    }
+    shared.sort_unstable_by_key(|(id, _)| *id);
+
+    for (id, mut unique) in shared {
+        while taken.contains(&unique) {
+            unique = format!("{unique}_{id}");
+        }
+        taken.insert(unique.clone());
+        names.insert(id, unique);
+    }
+
+    names
+}
+
+/// Names an image type after its GLSL counterpart, e.g.
+/// `tex2DArray<f32,sampled>` or `img3D<rgba16f,storage>`. The format replaces
+/// the sampled type if it is known
+fn image_type_name(instruction: &Instruction, sampled_type_name: String) -> Result<String> {
+    let dim = instruction.dim(1)?;
+    let depth = instruction.literal_int32(2)?;
+    let arrayed = instruction.literal_int32(3)? == 1;
+    let multisampled = instruction.literal_int32(4)? == 1;
+    let sampled = instruction.literal_int32(5)?;
+    let format = instruction.image_format(6)?;
+
+    // Whether an image is sampled may only be known at run time in kernels
+    let (prefix, usage) = match (dim, sampled) {
+        (Dim::DimSubpassData, _) => ("subpass", None),
+        (Dim::DimTileImageDataEXT, _) => ("attachment", None),
+        (_, 1) => ("tex", Some("sampled")),
+        (_, 2) => ("img", Some("storage")),
+        _ => ("image", None),
+    };
+    let dim_name = match dim {
+        Dim::Dim1D => "1D",
+        Dim::Dim2D => "2D",
+        Dim::Dim3D => "3D",
+        Dim::DimCube => "Cube",
+        Dim::DimRect => "Rect",
+        Dim::DimBuffer => "Buffer",
+        Dim::DimSubpassData | Dim::DimTileImageDataEXT => "",
+    };
+
+    let mut parameters = vec![match format {
+        ImageFormat::Unknown => sampled_type_name,
+        format => image_format_name(format),
+    }];
+    // A depth of 2 means unknown
+    if depth == 1 {
+        parameters.push("depth".to_string());
+    }
+    parameters.extend(usage.map(|usage| usage.to_string()));
+    if instruction.operands.len() > 7 {
+        parameters.push(access_qualifier_name(instruction.access_qualifier(7)?).to_string());
+    }
+
+    Ok(format!(
+        "{prefix}{dim_name}{}{}<{}>",
+        if multisampled { "MS" } else { "" },
+        if arrayed { "Array" } else { "" },
+        parameters.join(","),
+    ))
+}
+
+/// The GLSL layout qualifier of an image format, e.g. `rgba8_snorm`
+fn image_format_name(format: ImageFormat) -> String {
+    match format {
+        ImageFormat::R11fG11fB10f => "r11f_g11f_b10f".to_string(),
+        ImageFormat::Rgb10A2 => "rgb10_a2".to_string(),
+        ImageFormat::Rgb10a2ui => "rgb10_a2ui".to_string(),
+        format => format!("{format:?}")
+            .to_lowercase()
+            .replace("snorm", "_snorm"),
+    }
+}
+
+/// A short and unique abbreviation for the pointer type names
+fn storage_class_name(storage_class: StorageClass) -> &'static str {
+    match storage_class {
+        StorageClass::UniformConstant => "UC",
+        StorageClass::Input => "I",
+        StorageClass::Uniform => "U",
+        StorageClass::Output => "O",
+        StorageClass::Workgroup => "W",
+        StorageClass::CrossWorkgroup => "CW",
+        StorageClass::Private => "P",
+        StorageClass::Function => "F",
+        StorageClass::Generic => "G",
+        StorageClass::PushConstant => "PC",
+        StorageClass::AtomicCounter => "ACtr",
+        StorageClass::Image => "Img",
+        StorageClass::StorageBuffer => "SB",
+        StorageClass::CallableDataKHR => "Call",
+        StorageClass::IncomingCallableDataKHR => "ICall",
+        StorageClass::RayPayloadKHR => "Ray",
+        StorageClass::HitAttributeKHR => "Hit",
+        StorageClass::IncomingRayPayloadKHR => "IRay",
+        StorageClass::ShaderRecordBufferKHR => "SRB",
+        StorageClass::PhysicalStorageBuffer => "PSB",
+        StorageClass::HitObjectAttributeNV => "HObj",
+        StorageClass::TaskPayloadWorkgroupEXT => "Task",
+        StorageClass::TileImageEXT => "Tile",
+        StorageClass::NodePayloadAMDX => "Node",
+        StorageClass::NodeOutputPayloadAMDX => "NodeOut",
+        StorageClass::CodeSectionINTEL => "Code",
+        StorageClass::DeviceOnlyINTEL => "Dev",
+        StorageClass::HostOnlyINTEL => "Host",
+    }
+}
+
+fn access_qualifier_name(access: AccessQualifier) -> &'static str {
+    match access {
+        AccessQualifier::ReadOnly => "read_only",
+        AccessQualifier::WriteOnly => "write_only",
+        AccessQualifier::ReadWrite => "read_write",
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InstructionDisassembly {
+    pub result: Option<String>,
+    pub result_type: Option<String>,
+    pub name: String,
+    pub operands: Vec<String>,
+}
+
+
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct InstructionDisassemblyLengths {
+    pub result: usize,
+    pub result_type: usize,
+    pub name: usize,
+    pub operands: Vec<usize>,
+}
+
+impl InstructionDisassemblyLengths {
+    pub fn for_instructions<'lt, It>(iterator: It, limit_result_name_length: Option<usize>) -> Self
+    where
+        It: IntoIterator<Item = &'lt InstructionDisassembly>,
+    {
+        let mut lengths = Self::default();
+
+        for instr in iterator {
+            lengths.result = lengths
+                .result
+                .max(instr.result.as_ref().map(|s| s.len()).unwrap_or(0));
+            lengths.result_type = lengths
+                .result_type
+                .max(instr.result_type.as_ref().map(|s| s.len()).unwrap_or(0));
+
+            lengths.name = lengths.name.max(instr.name.len());
+
+            if lengths.operands.len() < instr.operands.len() {
+                lengths.operands.resize(instr.operands.len(), 0);
+            }
+
+            for (operand_length, operand) in lengths.operands.iter_mut().zip(instr.operands.iter())
+            {
+                *operand_length = (*operand_length).max(operand.len());
+            }
+        }
+
+        if let Some(limit_result_name_length) = limit_result_name_length {
+            lengths.result = lengths.result.min(limit_result_name_length);
+        }
+
+        lengths
+    }
+
+    pub fn format_instruction(
+        &self,
+        instruction: &InstructionDisassembly,
+        pad_operands: bool,
+    ) -> String {
+        let Self {
+            result: result_len,
+            result_type: mut result_type_len,
+            name: mut name_len,
+            operands: operands_len,
+        } = self;
+
+        let operands = if pad_operands {
+            operands_len
+                .iter()
+                .zip(instruction.operands.iter())
+                .map(|(length, operand)| format!("{:length$}", operand))
+                .join(" ")
+        } else {
+            result_type_len = 0;
+            name_len = 0;
+
+            instruction.operands.iter().join(" ")
+        };
+
+        let result = instruction
+            .result
+            .as_ref()
+            .map(|s| s.as_str())
+            .unwrap_or("");
+        let result_type = instruction
+            .result_type
+            .as_ref()
+            .map(|s| s.as_str())
+            .unwrap_or("");
+        let name = &instruction.name;
+
+        format!("{result:result_len$} {name:name_len$} {result_type:result_type_len$} {operands}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assembler::assemble, diagnostics::Diagnostics};
+    use rspirv::binary::Assemble;
+
+    fn int(bits: u32, signed: bool) -> Option<ScalarType> {
+        Some(ScalarType::Int { bits, signed })
+    }
+
+    fn float(bits: u32) -> Option<ScalarType> {
+        Some(ScalarType::Float { bits })
+    }
+
+    /// Names of the constants declared in `constants`, in order
+    fn constant_names(constants: &str) -> Vec<String> {
+        let module = assemble(&format!(
+            "OpCapability Shader
+             OpCapability Int8
+             OpCapability Int16
+             OpCapability Int64
+             OpCapability Float16
+             OpCapability Float64
+             OpMemoryModel Logical GLSL450
+             OpName %S \"S\"
+      %i8 = OpTypeInt 8 1
+     %i16 = OpTypeInt 16 1
+     %u16 = OpTypeInt 16 0
+     %i32 = OpTypeInt 32 1
+     %u32 = OpTypeInt 32 0
+     %i64 = OpTypeInt 64 1
+     %u64 = OpTypeInt 64 0
+     %f16 = OpTypeFloat 16
+     %f32 = OpTypeFloat 32
+     %f64 = OpTypeFloat 64
+    %vec2 = OpTypeVector %f32 2
+   %u32_2 = OpConstant %u32 2
+   %array = OpTypeArray %i32 %u32_2
+       %S = OpTypeStruct %vec2 %array
+             {constants}"
+        ))
+        .unwrap();
+        let info = ModuleInfo::create(&module).unwrap();
+
+        module
+            .types_global_values
+            .iter()
+            .filter(|instruction| {
+                matches!(
+                    instruction.class.opcode,
+                    Op::Constant | Op::ConstantComposite
+                )
+            })
+            .skip(1)
+            .map(|instruction| info.names[&instruction.result_id.unwrap()].clone())
+            .collect()
+    }
+
+    #[test]
+    fn narrow_signed_integers_are_sign_extended() {
+        let value = |v: u32, scalar_type| scalar_value(&Operand::LiteralBit32(v), scalar_type);
+
+        assert_eq!(value(0xffff_ff80, int(8, true)), "-128");
+        assert_eq!(value(0x7f, int(8, true)), "127");
+        assert_eq!(value(0xffff_ffff, int(16, true)), "-1");
+        assert_eq!(value(0xffff_8000, int(16, true)), "-32768");
+        assert_eq!(value(0xffff_fffd, int(32, true)), "-3");
+        // Producers that do not sign extend still give the right value
+        assert_eq!(value(0x80, int(8, true)), "-128");
+        assert_eq!(value(0xffff, int(16, true)), "-1");
+        // Unsigned types are not extended
+        assert_eq!(value(0xffff, int(16, false)), "65535");
+        assert_eq!(value(0xffff_ffff, int(32, false)), "4294967295");
+    }
+
+    #[test]
+    fn integers_without_bits_are_shown_as_is() {
+        assert_eq!(scalar_value(&Operand::LiteralBit32(5), int(0, true)), "5");
+
+        // The assembler rejects them, so the width is patched afterwards
+        let mut module = assemble(
+            "OpCapability Shader
+        %1 = OpTypeInt 32 1
+        %2 = OpConstant %1 7",
+        )
+        .unwrap();
+        module.types_global_values[0].operands[0] = Operand::LiteralBit32(0);
+        let info = ModuleInfo::create(&module).unwrap();
+        assert_eq!(info.names[&1], "i0");
+        assert_eq!(info.names[&2], "7i0");
+    }
+
+    #[test]
+    fn wide_integers() {
+        let value = |v: u64, scalar_type| scalar_value(&Operand::LiteralBit64(v), scalar_type);
+
+        assert_eq!(value(u64::MAX - 2, int(64, true)), "-3");
+        assert_eq!(value(u64::MAX, int(64, false)), "18446744073709551615");
+        assert_eq!(value(0x1_0000_0005, int(64, true)), "4294967301");
+    }
+
+    #[test]
+    fn wide_integers_are_split_across_two_words() {
+        // Low order word first
+        let mut module = assemble(
+            "OpCapability Shader
+             OpCapability Int64
+        %1 = OpTypeInt 64 1
+        %2 = OpConstant %1 0x100000005",
+        )
+        .unwrap();
+        let words = module.assemble();
+        assert_eq!(words[words.len() - 2..], [0x0000_0005, 0x0000_0001]);
+
+        // Loaded back from the words, the halves are joined again
+        module = rspirv::dr::load_words(&words).unwrap();
+        let info = ModuleInfo::create(&module).unwrap();
+        assert_eq!(info.names[&2], "4294967301i64");
+    }
+
+    #[test]
+    fn half_floats() {
+        assert_eq!(f16_value(0x3c00), 1.0);
+        assert_eq!(f16_value(0xc000), -2.0);
+        assert_eq!(f16_value(0x7bff), 65504.0);
+        // Subnormals, the smallest and the largest
+        assert_eq!(f16_value(0x0001), 2f32.powi(-24));
+        assert_eq!(f16_value(0x03ff), 1023.0 * 2f32.powi(-24));
+        assert_eq!(f16_value(0x8001), -(2f32.powi(-24)));
+        assert_eq!(f16_value(0x7c00), f32::INFINITY);
+        assert_eq!(f16_value(0xfc00), f32::NEG_INFINITY);
+        assert!(f16_value(0x7e00).is_nan());
+        assert!(f16_value(0x7c01).is_nan());
+
+        let zero = f16_value(0x0000);
+        let negative_zero = f16_value(0x8000);
+        assert!(zero == 0.0 && zero.is_sign_positive());
+        assert!(negative_zero == 0.0 && negative_zero.is_sign_negative());
+    }
+
+    #[test]
+    fn half_float_constants() {
+        let value = |bits: u32| scalar_value(&Operand::LiteralBit32(bits), float(16));
+        assert_eq!(value(0x3800), "0.5");
+        assert_eq!(value(0x8000), "-0.0");
+        assert_eq!(value(0x0001), "5.9604645e-8");
+        assert_eq!(value(0x7c00), "inf");
+        assert_eq!(value(0xfc00), "-inf");
+        assert_eq!(value(0x7e00), "NaN");
+        assert_eq!(value(0xbc00), "-1.0");
+    }
+
+    #[test]
+    fn float_constants() {
+        assert_eq!(
+            scalar_value(&Operand::LiteralBit32((-0.0f32).to_bits()), float(32)),
+            "-0.0"
+        );
+        assert_eq!(
+            scalar_value(&Operand::LiteralBit64((-2.25f64).to_bits()), float(64)),
+            "-2.25"
+        );
+        assert_eq!(
+            scalar_value(&Operand::LiteralBit32(0xbf80_0000), float(32)),
+            "-1.0"
+        );
+    }
+
+    #[test]
+    fn composite_values() {
+        let vector = (3, "f32".to_string());
+        let components = ["1.0".to_string(), "2.0".to_string(), "-0.0".to_string()];
+        assert_eq!(
+            composite_value("3xf32", Some(&vector), false, &components),
+            "vec3(1.0,2.0,-0.0)"
+        );
+        assert_eq!(
+            composite_value("[f32;3]", None, true, &components),
+            "[1.0,2.0,-0.0]"
+        );
+        assert_eq!(
+            composite_value("S", None, false, &components),
+            "S(1.0,2.0,-0.0)"
+        );
+
+        // Vectors without a GLSL name are written like structs
+        let half_vector = (2, "f16".to_string());
+        assert_eq!(
+            composite_value("2xf16", Some(&half_vector), false, &components[..2]),
+            "2xf16(1.0,2.0)"
+        );
+    }
+
+    #[test]
+    fn scalar_constant_names() {
+        assert_eq!(
+            constant_names(
+                "%a = OpConstant %i8 -128
+                 %b = OpConstant %i16 -1
+                 %c = OpConstant %u16 65535
+                 %d = OpConstant %i64 -3
+                 %e = OpConstant %f16 -0.5
+                 %f = OpConstant %f32 -0
+                 %g = OpConstant %f64 1e100"
+            ),
+            ["-128i8", "-1i16", "65535u16", "-3i64", "-0.5f16", "-0.0f32", "1e100f64"]
+        );
+    }
+
+    #[test]
+    fn nested_composites() {
+        assert_eq!(
+            constant_names(
+                "%one = OpConstant %f32 1
+                 %neg = OpConstant %f32 -2.5
+                   %v = OpConstantComposite %vec2 %one %neg
+                  %m3 = OpConstant %i32 -3
+                   %a = OpConstantComposite %array %m3 %m3
+                   %s = OpConstantComposite %S %v %a"
+            )[2..],
+            [
+                "vec2(1.0,-2.5)",
+                "-3i32",
+                "[-3,-3]",
+                "S(vec2(1.0,-2.5),[-3,-3])"
+            ]
+        );
+    }
+
+    #[test]
+    fn negative_and_half_constants_assemble_back() {
+        let text = "; Version: 1.0
+             OpCapability Shader
+             OpCapability Int16
+             OpCapability Float16
+             OpMemoryModel Logical GLSL450
+     %i16 = OpTypeInt 16 1
+     %i32 = OpTypeInt 32 1
+     %f16 = OpTypeFloat 16
+       %a = OpConstant %i16 -2
+       %b = OpConstant %i32 -3
+       %c = OpConstant %f16 -0.5
+       %d = OpConstant %f16 6e-8
+       %e = OpConstant %f16 -0
+       %f = OpConstant %f16 65504";
+        let module = assemble(text).unwrap();
+
+        let assembly = crate::annotate_module(&module, None, "", &Diagnostics::default()).unwrap();
+        let disassembly = assembly
+            .instructions
+            .iter()
+            .map(|instruction| instruction.instruction.as_str())
+            .join("\n");
+        let again = assemble(&format!("; Version: 1.0\n{disassembly}")).unwrap();
+
+        assert_eq!(module.assemble(), again.assemble());
+    }
+
+    #[test]
+    fn storage_class_abbreviations_are_unique() {
+        let storage_classes = (0..=u16::MAX.into())
+            .filter_map(StorageClass::from_u32)
+            .collect_vec();
+        let abbreviations = storage_classes
+            .iter()
+            .map(|storage_class| storage_class_name(*storage_class))
+            .collect::<HashSet<_>>();
+        assert_eq!(abbreviations.len(), storage_classes.len());
+    }
+
+    #[test]
+    fn pointers_to_newer_storage_classes() {
+        let module = assemble(
+            "OpCapability Shader
+             OpCapability MeshShadingEXT
+             OpCapability ShaderInvocationReorderNV
+             OpCapability TileImageColorReadAccessEXT
+             OpMemoryModel Logical GLSL450
+        %f32 = OpTypeFloat 32
+       %task = OpTypePointer TaskPayloadWorkgroupEXT %f32
+ %hit_object = OpTypePointer HitObjectAttributeNV %f32
+       %tile = OpTypePointer TileImageEXT %f32",
+        )
+        .unwrap();
+        let info = ModuleInfo::create(&module).unwrap();
+
+        let pointers = module
+            .types_global_values
+            .iter()
+            .filter(|instruction| instruction.class.opcode == Op::TypePointer)
+            .map(|instruction| info.names[&instruction.result_id.unwrap()].as_str())
+            .collect_vec();
+        assert_eq!(pointers, ["pTask*f32", "pHObj*f32", "pTile*f32"]);
+    }
+
+    #[test]
+    fn forward_pointers_to_recursive_structs() {
+        let module = assemble(
+            "OpCapability Shader
+             OpCapability PhysicalStorageBufferAddresses
+             OpMemoryModel PhysicalStorageBuffer64 GLSL450
+             OpName %Node \"Node\"
+             OpTypeForwardPointer %p_node PhysicalStorageBuffer
+             OpTypeForwardPointer %p_list PhysicalStorageBuffer
+        %f32 = OpTypeFloat 32
+       %Node = OpTypeStruct %f32 %p_node
+     %p_node = OpTypePointer PhysicalStorageBuffer %Node
+       %List = OpTypeStruct %f32 %p_list
+     %p_list = OpTypePointer PhysicalStorageBuffer %List",
+        )
+        .unwrap();
+        let info = ModuleInfo::create(&module).unwrap();
+
+        let names = module
+            .types_global_values
+            .iter()
+            .map(|instruction| {
+                let id = instruction
+                    .result_id
+                    .unwrap_or_else(|| instruction.id_ref(0).unwrap());
+                info.names[&id].as_str()
+            })
+            .collect_vec();
+        // The unnamed struct refers to itself by its id
+        assert_eq!(
+            names,
+            [
+                "pPSB*Node",
+                "pPSB*struct{f32;pPSB*5}",
+                "f32",
+                "Node",
+                "pPSB*Node",
+                "struct{f32;pPSB*5}",
+                "pPSB*struct{f32;pPSB*5}",
+            ]
+        );
+    }
+
+    /// The names of the non-scalar types, in declaration order
+    fn opaque_type_names(text: &str) -> Vec<String> {
+        let module = assemble(text).unwrap();
+        let info = ModuleInfo::create(&module).unwrap();
+        module
+            .types_global_values
+            .iter()
+            .filter(|instruction| {
+                !matches!(
+                    instruction.class.opcode,
+                    Op::TypeFloat | Op::TypeInt | Op::TypeVoid
+                )
+            })
+            .map(|instruction| info.names[&instruction.result_id.unwrap()].clone())
+            .collect()
+    }
+
+    #[test]
+    fn image_type_names() {
+        let names = opaque_type_names(
+            "OpCapability Shader
+             OpCapability ImageMSArray
+             OpCapability StorageImageExtendedFormats
+             OpMemoryModel Logical GLSL450
+        %f32 = OpTypeFloat 32
+        %i32 = OpTypeInt 32 1
+      %tex2D = OpTypeImage %f32 2D 0 0 0 1 Unknown
+      %img3D = OpTypeImage %f32 3D 0 0 0 2 Rgba16f
+     %shadow = OpTypeImage %f32 2D 1 0 0 1 Unknown
+   %ms_array = OpTypeImage %i32 2D 0 1 1 1 Unknown
+ %cube_array = OpTypeImage %f32 Cube 1 1 0 1 Unknown
+    %texels = OpTypeImage %f32 Buffer 0 0 0 2 R11fG11fB10f
+   %subpass = OpTypeImage %f32 SubpassData 0 0 0 2 Unknown
+    %kernel = OpTypeImage %f32 1D 2 0 0 0 Unknown",
+        );
+        assert_eq!(
+            names,
+            [
+                "tex2D<f32,sampled>",
+                "img3D<rgba16f,storage>",
+                "tex2D<f32,depth,sampled>",
+                "tex2DMSArray<i32,sampled>",
+                "texCubeArray<f32,depth,sampled>",
+                "imgBuffer<r11f_g11f_b10f,storage>",
+                "subpass<f32>",
+                "image1D<f32>",
+            ]
+        );
+    }
+
+    #[test]
+    fn sampler_and_ray_query_type_names() {
+        let names = opaque_type_names(
+            "OpCapability Shader
+             OpCapability RayQueryKHR
+             OpMemoryModel Logical GLSL450
+        %f32 = OpTypeFloat 32
+ %tex_array = OpTypeImage %f32 2D 0 1 0 1 Unknown
+   %sampler = OpTypeSampler
+  %combined = OpTypeSampledImage %tex_array
+     %query = OpTypeRayQueryKHR",
+        );
+        assert_eq!(
+            names,
+            [
+                "tex2DArray<f32,sampled>",
+                "sampler",
+                "combined<tex2DArray<f32>>",
+                "rayQuery",
+            ]
+        );
+    }
+
+    fn unique(names: &[(Word, &str)]) -> Vec<(Word, String)> {
+        let names = names
+            .iter()
+            .map(|(id, name)| (*id, name.to_string()))
+            .collect();
+        unique_names(names).into_iter().sorted().collect()
+    }
+
+    fn named(names: &[(Word, &str)]) -> Vec<(Word, String)> {
+        names
+            .iter()
+            .map(|(id, name)| (*id, name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn unique_names_are_kept() {
+        assert_eq!(unique(&[(1, "x"), (2, "y")]), named(&[(1, "x"), (2, "y")]));
+    }
+
+    #[test]
+    fn shared_names_get_their_id() {
+        assert_eq!(
+            unique(&[(7, "x"), (3, "x"), (5, "x"), (4, "y")]),
+            named(&[(3, "x_3"), (4, "y"), (5, "x_5"), (7, "x_7")])
+        );
+    }
+
+    #[test]
+    fn suffixed_names_that_are_taken_get_another_suffix() {
+        // x_5 is a name of its own, so the x with id 5 becomes x_5_5
+        assert_eq!(
+            unique(&[(5, "x"), (9, "x"), (12, "x_5")]),
+            named(&[(5, "x_5_5"), (9, "x_9"), (12, "x_5")])
+        );
+        // Until that is taken as well
+        assert_eq!(
+            unique(&[(5, "x"), (9, "x"), (12, "x_5"), (13, "x_5_5")]),
+            named(&[(5, "x_5_5_5"), (9, "x_9"), (12, "x_5"), (13, "x_5_5")])
+        );
+    }
+
+    #[test]
+    fn other_ids_do_not_renumber_suffixes() {
+        let before = unique(&[(3, "x"), (5, "x"), (8, "y")]);
+        assert_eq!(before, named(&[(3, "x_3"), (5, "x_5"), (8, "y")]));
+
+        // Neither an unrelated id before them nor another one with the same name
+        let after = unique(&[(1, "z"), (3, "x"), (4, "x"), (5, "x"), (8, "y")]);
+        assert_eq!(
+            after,
+            named(&[(1, "z"), (3, "x_3"), (4, "x_4"), (5, "x_5"), (8, "y")])
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use eyre::{eyre, Result};
+use rspirv::dr::{Instruction, Operand};
+use spirv::{AccessQualifier, Decoration, Dim, ImageFormat, StorageClass, Word};
+
+/// Checked access to instruction operands.
+///
+/// rspirv's `unwrap_*` accessors panic on malformed modules. These return an
+/// error naming the offending instruction instead.
+pub trait InstructionOperands {
+    fn result_id(&self) -> Result<Word>;
+    fn result_type(&self) -> Result<Word>;
+
+    fn operand(&self, index: usize) -> Result<&Operand>;
+    fn id_ref(&self, index: usize) -> Result<Word>;
+    fn literal_int32(&self, index: usize) -> Result<u32>;
+    fn literal_string(&self, index: usize) -> Result<&str>;
+    fn storage_class(&self, index: usize) -> Result<StorageClass>;
+    fn decoration(&self, index: usize) -> Result<Decoration>;
+    fn dim(&self, index: usize) -> Result<Dim>;
+    fn image_format(&self, index: usize) -> Result<ImageFormat>;
+    fn access_qualifier(&self, index: usize) -> Result<AccessQualifier>;
+}
+
+impl InstructionOperands for Instruction {
+    fn result_id(&self) -> Result<Word> {
+        self.result_id
+            .ok_or_else(|| eyre!("Op{} has no result id", self.class.opname))
+    }
+
+    fn result_type(&self) -> Result<Word> {
+        self.result_type
+            .ok_or_else(|| eyre!("Op{} has no result type", self.class.opname))
+    }
+
+    fn operand(&self, index: usize) -> Result<&Operand> {
+        self.operands.get(index).ok_or_else(|| {
+            eyre!(
+                "Op{} is missing operand {index} (has {})",
+                self.class.opname,
+                self.operands.len()
+            )
+        })
+    }
+
+    fn id_ref(&self, index: usize) -> Result<Word> {
+        match self.operand(index)? {
+            Operand::IdRef(id) => Ok(*id),
+            other => Err(unexpected_operand(self, index, "an id", other)),
+        }
+    }
+
+    fn literal_int32(&self, index: usize) -> Result<u32> {
+        match self.operand(index)? {
+            Operand::LiteralBit32(value) => Ok(*value),
+            other => Err(unexpected_operand(self, index, "a 32 bit literal", other)),
+        }
+    }
+
+    fn literal_string(&self, index: usize) -> Result<&str> {
+        match self.operand(index)? {
+            Operand::LiteralString(value) => Ok(value),
+            other => Err(unexpected_operand(self, index, "a string literal", other)),
+        }
+    }
+
+    fn storage_class(&self, index: usize) -> Result<StorageClass> {
+        match self.operand(index)? {
+            Operand::StorageClass(value) => Ok(*value),
+            other => Err(unexpected_operand(self, index, "a storage class", other)),
+        }
+    }
+
+    fn decoration(&self, index: usize) -> Result<Decoration> {
+        match self.operand(index)? {
+            Operand::Decoration(value) => Ok(*value),
+            other => Err(unexpected_operand(self, index, "a decoration", other)),
+        }
+    }
+
+    fn dim(&self, index: usize) -> Result<Dim> {
+        match self.operand(index)? {
+            Operand::Dim(value) => Ok(*value),
+            other => Err(unexpected_operand(self, index, "a dimension", other)),
+        }
+    }
+
+    fn image_format(&self, index: usize) -> Result<ImageFormat> {
+        match self.operand(index)? {
+            Operand::ImageFormat(value) => Ok(*value),
+            other => Err(unexpected_operand(self, index, "an image format", other)),
+        }
+    }
+
+    fn access_qualifier(&self, index: usize) -> Result<AccessQualifier> {
+        match self.operand(index)? {
+            Operand::AccessQualifier(value) => Ok(*value),
+            other => Err(unexpected_operand(
+                self,
+                index,
+                "an access qualifier",
+                other,
+            )),
+        }
+    }
+}
+
+fn unexpected_operand(
+    instruction: &Instruction,
+    index: usize,
+    expected: &str,
+    found: &Operand,
+) -> eyre::Report {
+    eyre!(
+        "Op{} operand {index} should be {expected}, found {found:?}",
+        instruction.class.opname
+    )
+}
@@ -0,0 +1,543 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShaderKind {
+    Vertex,
+    Fragment,
+    Geometry,
+    TesselationControl,
+    TesselationEvaluation,
+
+    RayGeneration,
+    AnyHit,
+    ClosestHit,
+    Miss,
+    Intersection,
+    Callable,
+
+    Compute,
+
+    Task,
+    Mesh,
+}
+
+impl ShaderKind {
+    /// Infers the shader kind from the file extensions used by glslangValidator
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Some(match extension {
+            "vert" => Self::Vertex,
+            "frag" => Self::Fragment,
+            "geom" => Self::Geometry,
+            "tesc" => Self::TesselationControl,
+            "tese" => Self::TesselationEvaluation,
+
+            "rgen" => Self::RayGeneration,
+            "rahit" => Self::AnyHit,
+            "rchit" => Self::ClosestHit,
+            "rmiss" => Self::Miss,
+            "rint" => Self::Intersection,
+            "rcall" => Self::Callable,
+
+            "comp" => Self::Compute,
+
+            "task" => Self::Task,
+            "mesh" => Self::Mesh,
+
+            _ => return None,
+        })
+    }
+}
+
+impl From<ShaderKind> for shaderc::ShaderKind {
+    fn from(kind: ShaderKind) -> Self {
+        match kind {
+            ShaderKind::Vertex => Self::Vertex,
+            ShaderKind::Fragment => Self::Fragment,
+            ShaderKind::Geometry => Self::Geometry,
+            ShaderKind::TesselationControl => Self::TessControl,
+            ShaderKind::TesselationEvaluation => Self::TessEvaluation,
+
+            ShaderKind::RayGeneration => Self::RayGeneration,
+            ShaderKind::AnyHit => Self::AnyHit,
+            ShaderKind::ClosestHit => Self::ClosestHit,
+            ShaderKind::Miss => Self::Miss,
+            ShaderKind::Intersection => Self::Intersection,
+            ShaderKind::Callable => Self::Callable,
+
+            ShaderKind::Compute => Self::Compute,
+
+            ShaderKind::Task => Self::Task,
+            ShaderKind::Mesh => Self::Mesh,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SourceLanguage {
+    #[serde(rename = "GLSL")]
+    Glsl,
+    #[serde(rename = "HLSL")]
+    Hlsl,
+}
+
+impl Default for SourceLanguage {
+    fn default() -> Self {
+        Self::Glsl
+    }
+}
+
+/// The SPIR-V consumer, independent of the source language
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TargetEnv {
+    Vulkan,
+    OpenGL,
+}
+
+impl Default for TargetEnv {
+    fn default() -> Self {
+        Self::Vulkan
+    }
+}
+
+impl TargetEnv {
+    pub fn default_version(self) -> TargetEnvVersion {
+        match self {
+            Self::Vulkan => TargetEnvVersion::V1_2,
+            Self::OpenGL => TargetEnvVersion::V4_5,
+        }
+    }
+}
+
+/// Vulkan versions `1.0` to `1.3`, or OpenGL `4.5`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TargetEnvVersion {
+    #[serde(rename = "1.0")]
+    V1_0,
+    #[serde(rename = "1.1")]
+    V1_1,
+    #[serde(rename = "1.2")]
+    V1_2,
+    #[serde(rename = "1.3")]
+    V1_3,
+    #[serde(rename = "4.5")]
+    V4_5,
+}
+
+impl fmt::Display for TargetEnvVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::V1_0 => "1.0",
+            Self::V1_1 => "1.1",
+            Self::V1_2 => "1.2",
+            Self::V1_3 => "1.3",
+            Self::V4_5 => "4.5",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpirvVersion {
+    #[serde(rename = "1.0")]
+    V1_0,
+    #[serde(rename = "1.1")]
+    V1_1,
+    #[serde(rename = "1.2")]
+    V1_2,
+    #[serde(rename = "1.3")]
+    V1_3,
+    #[serde(rename = "1.4")]
+    V1_4,
+    #[serde(rename = "1.5")]
+    V1_5,
+    #[serde(rename = "1.6")]
+    V1_6,
+}
+
+impl From<SpirvVersion> for shaderc::SpirvVersion {
+    fn from(version: SpirvVersion) -> Self {
+        match version {
+            SpirvVersion::V1_0 => Self::V1_0,
+            SpirvVersion::V1_1 => Self::V1_1,
+            SpirvVersion::V1_2 => Self::V1_2,
+            SpirvVersion::V1_3 => Self::V1_3,
+            SpirvVersion::V1_4 => Self::V1_4,
+            SpirvVersion::V1_5 => Self::V1_5,
+            SpirvVersion::V1_6 => Self::V1_6,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptimizationLevel {
+    Zero,
+    Size,
+    Performance,
+}
+
+impl Default for OptimizationLevel {
+    fn default() -> Self {
+        Self::Zero
+    }
+}
+
+impl From<OptimizationLevel> for shaderc::OptimizationLevel {
+    fn from(level: OptimizationLevel) -> Self {
+        match level {
+            OptimizationLevel::Zero => Self::Zero,
+            OptimizationLevel::Size => Self::Size,
+            OptimizationLevel::Performance => Self::Performance,
+        }
+    }
+}
+
+/// A single spirv-opt pass, named after its command line flag
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptimizerPass {
+    #[serde(rename = "inline-entry-points-exhaustive")]
+    InlineExhaustive,
+    #[serde(rename = "inline-entry-points-opaque")]
+    InlineOpaque,
+    #[serde(rename = "merge-return")]
+    MergeReturn,
+    #[serde(rename = "eliminate-local-single-block")]
+    EliminateLocalSingleBlock,
+    #[serde(rename = "eliminate-local-single-store")]
+    EliminateLocalSingleStore,
+    #[serde(rename = "eliminate-local-multi-store")]
+    EliminateLocalMultiStore,
+    #[serde(rename = "ssa-rewrite")]
+    SsaRewrite,
+    #[serde(rename = "convert-local-access-chains")]
+    ConvertLocalAccessChains,
+    #[serde(rename = "combine-access-chains")]
+    CombineAccessChains,
+    #[serde(rename = "private-to-local")]
+    PrivateToLocal,
+    #[serde(rename = "copy-propagate-arrays")]
+    CopyPropagateArrays,
+    #[serde(rename = "ccp")]
+    Ccp,
+    #[serde(rename = "simplify-instructions")]
+    SimplifyInstructions,
+    #[serde(rename = "strength-reduction")]
+    StrengthReduction,
+    #[serde(rename = "redundancy-elimination")]
+    RedundancyElimination,
+    #[serde(rename = "local-redundancy-elimination")]
+    LocalRedundancyElimination,
+    #[serde(rename = "eliminate-insert-extract")]
+    EliminateInsertExtract,
+    #[serde(rename = "eliminate-dead-inserts")]
+    EliminateDeadInserts,
+    #[serde(rename = "vector-dce")]
+    VectorDce,
+    #[serde(rename = "reduce-load-size")]
+    ReduceLoadSize,
+    #[serde(rename = "if-conversion")]
+    IfConversion,
+    #[serde(rename = "eliminate-dead-branches")]
+    EliminateDeadBranches,
+    #[serde(rename = "merge-blocks")]
+    MergeBlocks,
+    #[serde(rename = "cfg-cleanup")]
+    CfgCleanup,
+    #[serde(rename = "loop-peeling")]
+    LoopPeeling,
+    #[serde(rename = "loop-invariant-code-motion")]
+    LoopInvariantCodeMotion,
+    #[serde(rename = "eliminate-dead-code-aggressive")]
+    EliminateDeadCodeAggressive,
+    #[serde(rename = "eliminate-dead-functions")]
+    EliminateDeadFunctions,
+    #[serde(rename = "eliminate-dead-variables")]
+    EliminateDeadVariables,
+    #[serde(rename = "eliminate-dead-const")]
+    EliminateDeadConst,
+    #[serde(rename = "fold-spec-const-op-composite")]
+    FoldSpecConstOpComposite,
+    #[serde(rename = "freeze-spec-const")]
+    FreezeSpecConst,
+    #[serde(rename = "unify-const")]
+    UnifyConst,
+    #[serde(rename = "remove-duplicates")]
+    RemoveDuplicates,
+    #[serde(rename = "compact-ids")]
+    CompactIds,
+    #[serde(rename = "strip-debug")]
+    StripDebug,
+}
+
+impl fmt::Display for OptimizerPass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InlineExhaustive => "inline-entry-points-exhaustive",
+            Self::InlineOpaque => "inline-entry-points-opaque",
+            Self::MergeReturn => "merge-return",
+            Self::EliminateLocalSingleBlock => "eliminate-local-single-block",
+            Self::EliminateLocalSingleStore => "eliminate-local-single-store",
+            Self::EliminateLocalMultiStore => "eliminate-local-multi-store",
+            Self::SsaRewrite => "ssa-rewrite",
+            Self::ConvertLocalAccessChains => "convert-local-access-chains",
+            Self::CombineAccessChains => "combine-access-chains",
+            Self::PrivateToLocal => "private-to-local",
+            Self::CopyPropagateArrays => "copy-propagate-arrays",
+            Self::Ccp => "ccp",
+            Self::SimplifyInstructions => "simplify-instructions",
+            Self::StrengthReduction => "strength-reduction",
+            Self::RedundancyElimination => "redundancy-elimination",
+            Self::LocalRedundancyElimination => "local-redundancy-elimination",
+            Self::EliminateInsertExtract => "eliminate-insert-extract",
+            Self::EliminateDeadInserts => "eliminate-dead-inserts",
+            Self::VectorDce => "vector-dce",
+            Self::ReduceLoadSize => "reduce-load-size",
+            Self::IfConversion => "if-conversion",
+            Self::EliminateDeadBranches => "eliminate-dead-branches",
+            Self::MergeBlocks => "merge-blocks",
+            Self::CfgCleanup => "cfg-cleanup",
+            Self::LoopPeeling => "loop-peeling",
+            Self::LoopInvariantCodeMotion => "loop-invariant-code-motion",
+            Self::EliminateDeadCodeAggressive => "eliminate-dead-code-aggressive",
+            Self::EliminateDeadFunctions => "eliminate-dead-functions",
+            Self::EliminateDeadVariables => "eliminate-dead-variables",
+            Self::EliminateDeadConst => "eliminate-dead-const",
+            Self::FoldSpecConstOpComposite => "fold-spec-const-op-composite",
+            Self::FreezeSpecConst => "freeze-spec-const",
+            Self::UnifyConst => "unify-const",
+            Self::RemoveDuplicates => "remove-duplicates",
+            Self::CompactIds => "compact-ids",
+            Self::StripDebug => "strip-debug",
+        })
+    }
+}
+
+impl From<OptimizerPass> for spirv_tools::opt::Passes {
+    fn from(pass: OptimizerPass) -> Self {
+        match pass {
+            OptimizerPass::InlineExhaustive => Self::InlineExhaustive,
+            OptimizerPass::InlineOpaque => Self::InlineOpaque,
+            OptimizerPass::MergeReturn => Self::MergeReturn,
+            OptimizerPass::EliminateLocalSingleBlock => Self::LocalSingleBlockLoadStoreElim,
+            OptimizerPass::EliminateLocalSingleStore => Self::LocalSingleStoreElim,
+            OptimizerPass::EliminateLocalMultiStore => Self::LocalMultiStoreElim,
+            OptimizerPass::SsaRewrite => Self::SSARewrite,
+            OptimizerPass::ConvertLocalAccessChains => Self::LocalAccessChainConvert,
+            OptimizerPass::CombineAccessChains => Self::CombineAccessChains,
+            OptimizerPass::PrivateToLocal => Self::PrivateToLocal,
+            OptimizerPass::CopyPropagateArrays => Self::CopyPropagateArrays,
+            OptimizerPass::Ccp => Self::ConditionalConstantPropagation,
+            OptimizerPass::SimplifyInstructions => Self::Simplification,
+            OptimizerPass::StrengthReduction => Self::StrengthReduction,
+            OptimizerPass::RedundancyElimination => Self::RedundancyElimination,
+            OptimizerPass::LocalRedundancyElimination => Self::LocalRedundancyElimination,
+            OptimizerPass::EliminateInsertExtract => Self::InsertExtractElim,
+            OptimizerPass::EliminateDeadInserts => Self::DeadInsertElim,
+            OptimizerPass::VectorDce => Self::VectorDCE,
+            OptimizerPass::ReduceLoadSize => Self::ReduceLoadSize,
+            OptimizerPass::IfConversion => Self::IfConversion,
+            OptimizerPass::EliminateDeadBranches => Self::DeadBranchElim,
+            OptimizerPass::MergeBlocks => Self::BlockMerge,
+            OptimizerPass::CfgCleanup => Self::CFGCleanup,
+            OptimizerPass::LoopPeeling => Self::LoopPeeling,
+            OptimizerPass::LoopInvariantCodeMotion => Self::LoopInvariantCodeMotion,
+            OptimizerPass::EliminateDeadCodeAggressive => Self::AggressiveDCE,
+            OptimizerPass::EliminateDeadFunctions => Self::EliminateDeadFunctions,
+            OptimizerPass::EliminateDeadVariables => Self::DeadVariableElimination,
+            OptimizerPass::EliminateDeadConst => Self::EliminateDeadConstant,
+            OptimizerPass::FoldSpecConstOpComposite => Self::FoldSpecConstantOpAndComposite,
+            OptimizerPass::FreezeSpecConst => Self::FreezeSpecConstantValue,
+            OptimizerPass::UnifyConst => Self::UnifyConstant,
+            OptimizerPass::RemoveDuplicates => Self::RemoveDuplicates,
+            OptimizerPass::CompactIds => Self::CompactIds,
+            OptimizerPass::StripDebug => Self::StripDebugInfo,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ResourceKind {
+    Image,
+    Sampler,
+    Texture,
+    Buffer,
+    StorageBuffer,
+    UnorderedAccessView,
+}
+
+impl From<ResourceKind> for shaderc::ResourceKind {
+    fn from(kind: ResourceKind) -> Self {
+        match kind {
+            ResourceKind::Image => Self::Image,
+            ResourceKind::Sampler => Self::Sampler,
+            ResourceKind::Texture => Self::Texture,
+            ResourceKind::Buffer => Self::Buffer,
+            ResourceKind::StorageBuffer => Self::StorageBuffer,
+            ResourceKind::UnorderedAccessView => Self::UnorderedAccessView,
+        }
+    }
+}
+
+/// HLSL register classes, as in `register(b0)`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HlslRegisterClass {
+    #[serde(rename = "b")]
+    ConstantBuffer,
+    #[serde(rename = "t")]
+    Texture,
+    #[serde(rename = "s")]
+    Sampler,
+    #[serde(rename = "u")]
+    UnorderedAccessView,
+}
+
+impl From<HlslRegisterClass> for shaderc::ResourceKind {
+    fn from(register: HlslRegisterClass) -> Self {
+        match register {
+            HlslRegisterClass::ConstantBuffer => Self::Buffer,
+            HlslRegisterClass::Texture => Self::Texture,
+            HlslRegisterClass::Sampler => Self::Sampler,
+            HlslRegisterClass::UnorderedAccessView => Self::UnorderedAccessView,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompileShaderOptions {
+    #[serde(default)]
+    pub file_name: Option<String>,
+
+    #[serde(default)]
+    pub source_language: SourceLanguage,
+
+    #[serde(default)]
+    pub target_env: TargetEnv,
+
+    /// Defaults to Vulkan 1.2 or OpenGL 4.5
+    #[serde(default)]
+    pub target_env_version: Option<TargetEnvVersion>,
+
+    /// Defaults to the highest version supported by the target environment
+    #[serde(default)]
+    pub spirv_version: Option<SpirvVersion>,
+
+    #[serde(default)]
+    pub limit_result_name_length: Option<usize>,
+
+    /// Only used for HLSL, GLSL entry points are always `main`
+    #[serde(default)]
+    pub entry_point: Option<String>,
+
+    #[serde(default)]
+    pub optimization: OptimizationLevel,
+
+    /// Values for specialization constants by `SpecId`, written like literals
+    /// in the assembly, e.g. `64`, `-1`, `0.5` or `true`. Overridden constants
+    /// are frozen into regular constants and folded
+    #[serde(default)]
+    pub specialization: BTreeMap<u32, String>,
+
+    /// Preprocessor macros, equivalent to `-DNAME` or `-DNAME=VALUE`
+    #[serde(default)]
+    pub macros: BTreeMap<String, Option<String>>,
+
+    /// In-memory files available to `#include`, keyed by their virtual path
+    #[serde(default)]
+    pub sources: BTreeMap<String, String>,
+
+    /// Directories on disk searched for `#include <...>` after the in-memory
+    /// files
+    #[serde(default)]
+    pub include_directories: Vec<String>,
+
+    #[serde(default)]
+    pub max_include_depth: Option<usize>,
+
+    /// Overrides the source's `#version`, e.g. `450core` or `310es`
+    #[serde(default)]
+    pub forced_version_profile: Option<String>,
+
+    /// Defaults to enabled for OpenGL and disabled for Vulkan
+    #[serde(default)]
+    pub auto_map_locations: Option<bool>,
+
+    /// Defaults to enabled for OpenGL and disabled for Vulkan
+    #[serde(default)]
+    pub auto_bind_uniforms: Option<bool>,
+
+    #[serde(default)]
+    pub auto_combined_image_sampler: bool,
+
+    /// Binding base for automatically bound resources
+    #[serde(default)]
+    pub binding_base: BTreeMap<ResourceKind, u32>,
+
+    #[serde(default)]
+    pub invert_y: bool,
+
+    #[serde(default)]
+    pub nan_clamp: bool,
+
+    /// Only applied when compiling HLSL
+    #[serde(default)]
+    pub hlsl: HlslOptions,
+}
+
+/// HLSL specific compile options.
+///
+/// Native 16-bit types (`-enable-16bit-types`) are still missing. libshaderc
+/// has `shaderc_compile_options_set_hlsl_16bit_types`, but no release of the
+/// shaderc crate forwards it and its `CompileOptions` keep the raw options
+/// private, so there is no way to set it short of calling libshaderc directly
+/// for the whole compilation.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HlslOptions {
+    /// Binding base per register class, like `-fvk-b-shift`, `-fvk-t-shift`,
+    /// `-fvk-s-shift` and `-fvk-u-shift`
+    #[serde(default)]
+    pub register_shifts: BTreeMap<HlslRegisterClass, u32>,
+
+    /// Explicit descriptor set and binding for individual registers, like
+    /// `-fresource-set-binding`
+    #[serde(default)]
+    pub register_bindings: Vec<HlslRegisterBinding>,
+
+    /// Emit `SPV_GOOGLE_hlsl_functionality1` decorations
+    #[serde(default)]
+    pub functionality1: bool,
+
+    /// Use HLSL packing rules instead of GLSL rules for buffer offsets
+    #[serde(default)]
+    pub offsets: bool,
+
+    /// Use HLSL register assignments for bindings
+    #[serde(default)]
+    pub io_mapping: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HlslRegisterBinding {
+    /// The register name, e.g. `t4`
+    pub register: String,
+    pub set: u32,
+    pub binding: u32,
+}
+
+/// Options for modules that are disassembled or assembled rather than compiled
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadOptions {
+    #[serde(default)]
+    pub limit_result_name_length: Option<usize>,
+
+    /// The environment the module is validated for. Without one, only the
+    /// universal rules of the module's SPIR-V version apply
+    #[serde(default)]
+    pub target_env: Option<TargetEnv>,
+
+    /// Defaults to Vulkan 1.2 or OpenGL 4.5
+    #[serde(default)]
+    pub target_env_version: Option<TargetEnvVersion>,
+}
@@ -0,0 +1,244 @@
+use crate::{
+    annotate,
+    annotated_disassembly::AnnotatedDisassembly,
+    compile_shader,
+    diagnostics::Diagnostics,
+    diff::{diff_instructions, InstructionDiff},
+    options::{CompileShaderOptions, OptimizerPass, ShaderKind},
+    validation, CompileError, CompiledShader,
+};
+use rspirv::{binary::Assemble, dr::Module};
+use serde::{Deserialize, Serialize};
+use spirv_tools::{
+    error::{Message, MessageLevel},
+    opt::{self, Optimizer},
+};
+
+/// The module after running a single optimizer pass
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PassStep {
+    pub pass: OptimizerPass,
+    pub assembly: AnnotatedDisassembly,
+    /// Instructions of this snapshot that did not exist before the pass
+    pub added: Vec<usize>,
+    /// Instructions of the previous snapshot that the pass removed
+    pub removed: Vec<usize>,
+    /// Instructions of this snapshot that replace an instruction of the
+    /// previous snapshot with the same opcode
+    pub changed: Vec<usize>,
+    /// Warnings printed by the optimizer
+    pub messages: Vec<String>,
+}
+
+/// A pass that could not be run. Every pass before it has a snapshot
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PassError {
+    pub pass: OptimizerPass,
+    pub error: String,
+}
+
+pub struct PassPipeline {
+    /// The compilation the passes start from, optimized at the level of the
+    /// options
+    pub initial: CompiledShader,
+    pub steps: Vec<PassStep>,
+    pub error: Option<PassError>,
+}
+
+/// Compiles a shader and runs the given optimizer passes on it one at a time,
+/// annotating the module after every pass.
+///
+/// The optimization level of the options is applied by shaderc before any of
+/// the passes run, so it should usually be `Zero`.
+pub fn run_passes(
+    source: &str,
+    shader_kind: ShaderKind,
+    options: &CompileShaderOptions,
+    passes: &[OptimizerPass],
+) -> Result<PassPipeline, CompileError> {
+    let initial = compile_shader(source, shader_kind, options)?;
+
+    let env = validation::validator_env(
+        Some((options.target_env, initial.target_env_version)),
+        initial.spirv_version,
+    )?;
+
+    let mut steps = Vec::<PassStep>::new();
+    let mut error = None;
+
+    let mut module = initial.module.clone();
+    for &pass in passes {
+        let previous = steps
+            .last()
+            .map(|step| &step.assembly)
+            .unwrap_or(&initial.assembly);
+
+        let mut optimizer = opt::create(env.env());
+        optimizer.register_pass(pass.into());
+
+        let mut messages = Vec::new();
+        let mut errors = Vec::new();
+        let result = optimizer.optimize(
+            module.assemble(),
+            &mut |message: Message| match message.level {
+                MessageLevel::Fatal | MessageLevel::InternalError | MessageLevel::Error => {
+                    errors.push(message.message)
+                },
+                MessageLevel::Warning => messages.push(message.message),
+                MessageLevel::Info | MessageLevel::Debug => (),
+            },
+            None,
+        );
+
+        let binary = match result {
+            Ok(binary) => binary,
+            Err(e) => {
+                errors.insert(0, e.to_string());
+                error = Some(PassError {
+                    pass,
+                    error: errors.join("\n"),
+                });
+                break;
+            },
+        };
+
+        let (optimized, mut assembly) = match annotate(
+            binary.as_words(),
+            options.limit_result_name_length,
+            "",
+            &Diagnostics::default(),
+        ) {
+            Ok(annotated) => annotated,
+            Err(e) => {
+                error = Some(PassError {
+                    pass,
+                    error: e.to_string(),
+                });
+                break;
+            },
+        };
+        assembly.attach_validation(validation::validate(binary.as_words(), &env));
+
+        let diff = InstructionChanges::between(&module, previous, &optimized, &assembly);
+        steps.push(PassStep {
+            pass,
+            assembly,
+            added: diff.added,
+            removed: diff.removed,
+            changed: diff.changed,
+            messages,
+        });
+        module = optimized;
+    }
+
+    Ok(PassPipeline {
+        initial,
+        steps,
+        error,
+    })
+}
+
+/// Positions in `AnnotatedDisassembly::instructions` affected by a pass
+struct InstructionChanges {
+    added: Vec<usize>,
+    removed: Vec<usize>,
+    changed: Vec<usize>,
+}
+
+impl InstructionChanges {
+    /// Aligns the snapshots like the shader diff does. Passes like compact-ids
+    /// renumber every id, so matching by result id would mark everything after
+    /// them as changed
+    fn between(
+        before: &Module,
+        before_assembly: &AnnotatedDisassembly,
+        after: &Module,
+        after_assembly: &AnnotatedDisassembly,
+    ) -> Self {
+        let mut changes = Self {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+
+        for line in diff_instructions(before, before_assembly, after, after_assembly) {
+            match line {
+                InstructionDiff::Equal { .. } => (),
+                InstructionDiff::Modify { right, .. } => changes.changed.push(right),
+                InstructionDiff::Delete { left } => changes.removed.push(left),
+                InstructionDiff::Insert { right } => changes.added.push(right),
+            }
+        }
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::assemble;
+
+    fn changes(before: &str, after: &str) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+        let annotated = |text: &str| {
+            let module = assemble(text).unwrap();
+            let assembly =
+                crate::annotate_module(&module, None, "", &Diagnostics::default()).unwrap();
+            (module, assembly)
+        };
+        let (before, before_assembly) = annotated(before);
+        let (after, after_assembly) = annotated(after);
+
+        let changes =
+            InstructionChanges::between(&before, &before_assembly, &after, &after_assembly);
+        (changes.added, changes.removed, changes.changed)
+    }
+
+    const MODULE: &str = "\
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %20 \"main\"
+        OpExecutionMode %20 LocalSize 1 1 1
+  %10 = OpTypeVoid
+  %11 = OpTypeFunction %10
+  %12 = OpTypeInt 32 1
+  %13 = OpConstant %12 3
+  %14 = OpTypePointer Function %12
+  %20 = OpFunction %10 None %11
+  %21 = OpLabel
+  %22 = OpVariable %14 Function
+        OpStore %22 %13
+        OpReturn
+        OpFunctionEnd
+";
+
+    /// Lowers every id by `offset`, like compact-ids does
+    fn renumber(text: &str, offset: u32) -> String {
+        let mut parts = text.split('%');
+        let mut renumbered = parts.next().unwrap_or_default().to_string();
+        for part in parts {
+            let digits = part
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(part.len());
+            let id = part[..digits].parse::<u32>().unwrap();
+            renumbered.push_str(&format!("%{}{}", id - offset, &part[digits..]));
+        }
+        renumbered
+    }
+
+    #[test]
+    fn renumbered_ids_are_unchanged() {
+        assert_eq!(
+            changes(MODULE, &renumber(MODULE, 9)),
+            (vec![], vec![], vec![])
+        );
+    }
+
+    #[test]
+    fn changes_are_found_despite_renumbering() {
+        let optimized = renumber(MODULE, 9)
+            .replace("OpConstant %3 3", "OpConstant %3 4")
+            .replace("        OpStore %13 %4\n", "");
+        assert_eq!(changes(MODULE, &optimized), (vec![], vec![12], vec![7]));
+    }
+}
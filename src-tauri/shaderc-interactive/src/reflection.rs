@@ -0,0 +1,891 @@
+use crate::operands::InstructionOperands;
+use eyre::Result;
+use rspirv::dr::{Instruction, Module, Operand};
+use serde::{Deserialize, Serialize};
+use spirv::{BuiltIn, Decoration, Dim, ExecutionModel, Op, StorageClass, Word};
+use std::collections::HashMap;
+
+/// Decorations with their extra operands
+type DecorationList = Vec<(Decoration, Vec<Operand>)>;
+
+/// The decorations applied to every id and struct member
+#[derive(Default)]
+pub struct Decorations {
+    ids: HashMap<Word, DecorationList>,
+    members: HashMap<(Word, u32), DecorationList>,
+}
+
+impl Decorations {
+    pub fn collect(module: &Module) -> Result<Self> {
+        let mut decorations = Self::default();
+
+        for instruction in module.annotations.iter() {
+            match instruction.class.opcode {
+                Op::Decorate | Op::DecorateId | Op::DecorateString => {
+                    let target = instruction.id_ref(0)?;
+                    let decoration = instruction.decoration(1)?;
+                    decorations
+                        .ids
+                        .entry(target)
+                        .or_default()
+                        .push((decoration, instruction.operands[2..].to_vec()));
+                },
+                Op::MemberDecorate | Op::MemberDecorateString => {
+                    let target = instruction.id_ref(0)?;
+                    let member = instruction.literal_int32(1)?;
+                    let decoration = instruction.decoration(2)?;
+                    decorations
+                        .members
+                        .entry((target, member))
+                        .or_default()
+                        .push((decoration, instruction.operands[3..].to_vec()));
+                },
+                _ => (),
+            }
+        }
+
+        Ok(decorations)
+    }
+
+    /// The extra operands of a decoration, if the id has it
+    pub fn get(&self, id: Word, decoration: Decoration) -> Option<&[Operand]> {
+        find(self.ids.get(&id), decoration)
+    }
+
+    pub fn has(&self, id: Word, decoration: Decoration) -> bool {
+        self.get(id, decoration).is_some()
+    }
+
+    /// The literal of a decoration like `Binding` or `Location`
+    pub fn literal(&self, id: Word, decoration: Decoration) -> Option<u32> {
+        first_literal(self.get(id, decoration))
+    }
+
+    pub fn member(&self, id: Word, member: u32, decoration: Decoration) -> Option<&[Operand]> {
+        find(self.members.get(&(id, member)), decoration)
+    }
+
+    pub fn member_literal(&self, id: Word, member: u32, decoration: Decoration) -> Option<u32> {
+        first_literal(self.member(id, member, decoration))
+    }
+}
+
+fn find(decorations: Option<&DecorationList>, decoration: Decoration) -> Option<&[Operand]> {
+    decorations?
+        .iter()
+        .find(|(d, _)| *d == decoration)
+        .map(|(_, operands)| operands.as_slice())
+}
+
+fn first_literal(operands: Option<&[Operand]>) -> Option<u32> {
+    match operands?.first()? {
+        Operand::LiteralBit32(value) => Some(*value),
+        _ => None,
+    }
+}
+
+/// The names given by `OpMemberName`, keyed by struct and member index
+pub fn member_names(module: &Module) -> Result<HashMap<(Word, u32), String>> {
+    module
+        .debug_names
+        .iter()
+        .filter(|instruction| instruction.class.opcode == Op::MemberName)
+        .map(|instruction| {
+            Ok((
+                (instruction.id_ref(0)?, instruction.literal_int32(1)?),
+                instruction.literal_string(2)?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// A variable bound through a descriptor set
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Resource {
+    pub variable: Word,
+    pub name: Option<String>,
+    /// Missing if the module leaves it to be assigned, e.g. for OpenGL
+    pub set: Option<u32>,
+    pub binding: Option<u32>,
+    pub storage_class: StorageClass,
+    /// Missing for variables that are not descriptors, e.g. loose OpenGL
+    /// uniforms
+    pub descriptor_type: Option<DescriptorType>,
+    pub array_size: Option<ArraySize>,
+    /// The friendly name of the element type of descriptor arrays
+    pub type_name: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DescriptorType {
+    UniformBuffer,
+    StorageBuffer,
+    Sampler,
+    SampledImage,
+    CombinedImageSampler,
+    StorageImage,
+    UniformTexelBuffer,
+    StorageTexelBuffer,
+    InputAttachment,
+    AccelerationStructure,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArraySize {
+    Constant(u64),
+    /// Sized by a specialization constant, given by its friendly name
+    SpecConstant(String),
+    Runtime,
+}
+
+/// Type and constant definitions by id, with their friendly names
+pub struct Definitions<'m> {
+    instructions: HashMap<Word, &'m Instruction>,
+    names: &'m HashMap<Word, String>,
+}
+
+impl<'m> Definitions<'m> {
+    pub fn new(module: &'m Module, names: &'m HashMap<Word, String>) -> Self {
+        let instructions = module
+            .types_global_values
+            .iter()
+            .filter_map(|instruction| Some((instruction.result_id?, instruction)))
+            .collect();
+
+        Self {
+            instructions,
+            names,
+        }
+    }
+
+    pub fn get(&self, id: Word) -> Option<&'m Instruction> {
+        self.instructions.get(&id).copied()
+    }
+
+    /// The definition, if it has the given opcode
+    pub fn get_op(&self, id: Word, opcode: Op) -> Option<&'m Instruction> {
+        self.get(id)
+            .filter(|instruction| instruction.class.opcode == opcode)
+    }
+
+    /// The friendly name, or the number of ids without one
+    pub fn name(&self, id: Word) -> String {
+        self.known_name(id).unwrap_or_else(|| id.to_string())
+    }
+
+    pub fn known_name(&self, id: Word) -> Option<String> {
+        self.names.get(&id).cloned()
+    }
+
+    /// The type pointed to by a global variable
+    pub fn pointee(&self, variable: &Instruction) -> Result<Option<Word>> {
+        match self.get_op(variable.result_type()?, Op::TypePointer) {
+            Some(pointer) => Ok(Some(pointer.id_ref(1)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Lists the variables in the storage classes that hold descriptors, in the
+/// order they are declared
+pub fn resources(
+    module: &Module,
+    definitions: &Definitions,
+    decorations: &Decorations,
+) -> Result<Vec<Resource>> {
+    let mut resources = Vec::new();
+
+    for instruction in module.types_global_values.iter() {
+        if instruction.class.opcode != Op::Variable {
+            continue;
+        }
+
+        let storage_class = instruction.storage_class(0)?;
+        if !matches!(
+            storage_class,
+            StorageClass::UniformConstant | StorageClass::Uniform | StorageClass::StorageBuffer
+        ) {
+            continue;
+        }
+
+        let variable = instruction.result_id()?;
+        let mut element = match definitions.pointee(instruction)? {
+            Some(pointee) => pointee,
+            None => continue,
+        };
+
+        let mut array_size = None;
+        if let Some(array) = definitions.get(element) {
+            match array.class.opcode {
+                Op::TypeArray => {
+                    array_size = Some(array_length(array.id_ref(1)?, definitions));
+                    element = array.id_ref(0)?;
+                },
+                Op::TypeRuntimeArray => {
+                    array_size = Some(ArraySize::Runtime);
+                    element = array.id_ref(0)?;
+                },
+                _ => (),
+            }
+        }
+
+        let descriptor_type = match definitions.get(element) {
+            Some(element_type) => {
+                descriptor_type(storage_class, element, element_type, decorations)?
+            },
+            None => None,
+        };
+
+        resources.push(Resource {
+            variable,
+            name: definitions.known_name(variable),
+            set: decorations.literal(variable, Decoration::DescriptorSet),
+            binding: decorations.literal(variable, Decoration::Binding),
+            storage_class,
+            descriptor_type,
+            array_size,
+            type_name: definitions.name(element),
+        });
+    }
+
+    Ok(resources)
+}
+
+/// A specialization constant, or a constant computed from them
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SpecConstant {
+    pub id: Word,
+    pub name: Option<String>,
+    /// Missing for composites and operations, which cannot be set directly
+    pub spec_id: Option<u32>,
+    pub type_name: String,
+    /// The default literal, or the expression of composites and operations
+    pub default: String,
+}
+
+/// Lists the specialization constants in the order they are declared
+pub fn spec_constants(
+    module: &Module,
+    definitions: &Definitions,
+    decorations: &Decorations,
+    defaults: &HashMap<Word, String>,
+) -> Result<Vec<SpecConstant>> {
+    // The friendly names include the default, so only OpName is used here
+    let debug_names = module
+        .debug_names
+        .iter()
+        .filter(|instruction| instruction.class.opcode == Op::Name)
+        .map(|instruction| {
+            Ok((
+                instruction.id_ref(0)?,
+                instruction.literal_string(1)?.to_string(),
+            ))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    module
+        .types_global_values
+        .iter()
+        .filter(|instruction| {
+            matches!(
+                instruction.class.opcode,
+                Op::SpecConstant
+                    | Op::SpecConstantTrue
+                    | Op::SpecConstantFalse
+                    | Op::SpecConstantComposite
+                    | Op::SpecConstantOp
+            )
+        })
+        .map(|instruction| {
+            let id = instruction.result_id()?;
+            Ok(SpecConstant {
+                id,
+                name: debug_names.get(&id).cloned(),
+                spec_id: decorations.literal(id, Decoration::SpecId),
+                type_name: definitions.name(instruction.result_type()?),
+                default: defaults.get(&id).cloned().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+fn array_length(length: Word, definitions: &Definitions) -> ArraySize {
+    match definitions
+        .get_op(length, Op::Constant)
+        .and_then(|constant| constant.operands.first())
+    {
+        Some(Operand::LiteralBit32(value)) => ArraySize::Constant((*value).into()),
+        Some(Operand::LiteralBit64(value)) => ArraySize::Constant(*value),
+        _ => ArraySize::SpecConstant(definitions.name(length)),
+    }
+}
+
+fn descriptor_type(
+    storage_class: StorageClass,
+    id: Word,
+    definition: &Instruction,
+    decorations: &Decorations,
+) -> Result<Option<DescriptorType>> {
+    Ok(Some(match definition.class.opcode {
+        Op::TypeStruct => match storage_class {
+            StorageClass::StorageBuffer => DescriptorType::StorageBuffer,
+            // Before SPIR-V 1.3, storage buffers are uniform blocks decorated as
+            // BufferBlock
+            StorageClass::Uniform if decorations.has(id, Decoration::BufferBlock) => {
+                DescriptorType::StorageBuffer
+            },
+            StorageClass::Uniform => DescriptorType::UniformBuffer,
+            _ => return Ok(None),
+        },
+        Op::TypeImage => {
+            let dim = match definition.operand(1)? {
+                Operand::Dim(dim) => *dim,
+                _ => return Ok(None),
+            };
+            // 1 means sampled, 2 means read and written without a sampler
+            let storage = definition.literal_int32(5)? == 2;
+
+            match (dim, storage) {
+                (Dim::DimSubpassData, _) => DescriptorType::InputAttachment,
+                (Dim::DimBuffer, true) => DescriptorType::StorageTexelBuffer,
+                (Dim::DimBuffer, false) => DescriptorType::UniformTexelBuffer,
+                (_, true) => DescriptorType::StorageImage,
+                (_, false) => DescriptorType::SampledImage,
+            }
+        },
+        Op::TypeSampler => DescriptorType::Sampler,
+        Op::TypeSampledImage => DescriptorType::CombinedImageSampler,
+        Op::TypeAccelerationStructureNV => DescriptorType::AccelerationStructure,
+        _ => return Ok(None),
+    }))
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EntryPoint {
+    pub name: String,
+    pub execution_model: ExecutionModel,
+    pub function: Word,
+    /// Inputs before outputs, each sorted by location with built-ins last
+    pub interface: Vec<InterfaceVariable>,
+}
+
+/// An `Input` or `Output` variable of an entry point
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InterfaceVariable {
+    pub variable: Word,
+    pub name: Option<String>,
+    pub storage_class: StorageClass,
+    pub type_name: String,
+    pub location: Option<u32>,
+    pub component: Option<u32>,
+    pub built_in: Option<BuiltIn>,
+    /// Interpolation and auxiliary decorations, like `Flat` or `Patch`
+    pub qualifiers: Vec<Decoration>,
+    /// Members of interface blocks, e.g. the built-ins of `gl_PerVertex`
+    pub members: Vec<InterfaceMember>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InterfaceMember {
+    pub index: u32,
+    pub name: Option<String>,
+    pub type_name: String,
+    pub location: Option<u32>,
+    pub component: Option<u32>,
+    pub built_in: Option<BuiltIn>,
+    pub qualifiers: Vec<Decoration>,
+}
+
+const QUALIFIERS: &[Decoration] = &[
+    Decoration::Flat,
+    Decoration::NoPerspective,
+    Decoration::Centroid,
+    Decoration::Sample,
+    Decoration::Patch,
+    Decoration::Invariant,
+    Decoration::PerPrimitiveNV,
+    Decoration::PerViewNV,
+    Decoration::PerTaskNV,
+    Decoration::PerVertexNV,
+];
+
+/// Lists the stage inputs and outputs of every entry point
+pub fn entry_points(
+    module: &Module,
+    definitions: &Definitions,
+    member_names: &HashMap<(Word, u32), String>,
+    decorations: &Decorations,
+) -> Result<Vec<EntryPoint>> {
+    module
+        .entry_points
+        .iter()
+        .map(|entry_point| {
+            let execution_model = match entry_point.operand(0)? {
+                Operand::ExecutionModel(model) => *model,
+                other => eyre::bail!("OpEntryPoint has no execution model, found {other:?}"),
+            };
+            let function = entry_point.id_ref(1)?;
+            let name = entry_point.literal_string(2)?.to_string();
+
+            let mut interface = Vec::new();
+            for index in 3..entry_point.operands.len() {
+                let variable = entry_point.id_ref(index)?;
+
+                // Since SPIR-V 1.4 the interface lists every global variable
+                let definition = match definitions.get_op(variable, Op::Variable) {
+                    Some(definition) => definition,
+                    None => continue,
+                };
+                let storage_class = definition.storage_class(0)?;
+                if !matches!(storage_class, StorageClass::Input | StorageClass::Output) {
+                    continue;
+                }
+                let pointee = match definitions.pointee(definition)? {
+                    Some(pointee) => pointee,
+                    None => continue,
+                };
+
+                interface.push(InterfaceVariable {
+                    variable,
+                    name: definitions.known_name(variable),
+                    storage_class,
+                    type_name: definitions.name(pointee),
+                    location: decorations.literal(variable, Decoration::Location),
+                    component: decorations.literal(variable, Decoration::Component),
+                    built_in: built_in(decorations.get(variable, Decoration::BuiltIn)),
+                    qualifiers: qualifiers(|qualifier| decorations.get(variable, qualifier)),
+                    members: interface_members(pointee, definitions, member_names, decorations)?,
+                });
+            }
+
+            interface.sort_by_key(|variable| {
+                (
+                    variable.storage_class != StorageClass::Input,
+                    variable.location.is_none(),
+                    variable.location,
+                    variable.component,
+                )
+            });
+
+            Ok(EntryPoint {
+                name,
+                execution_model,
+                function,
+                interface,
+            })
+        })
+        .collect()
+}
+
+/// The members of an interface block, which may be wrapped in arrays, e.g. for
+/// tessellation
+fn interface_members(
+    mut block: Word,
+    definitions: &Definitions,
+    member_names: &HashMap<(Word, u32), String>,
+    decorations: &Decorations,
+) -> Result<Vec<InterfaceMember>> {
+    while let Some(array) = definitions.get(block) {
+        match array.class.opcode {
+            Op::TypeArray | Op::TypeRuntimeArray => block = array.id_ref(0)?,
+            _ => break,
+        }
+    }
+
+    let block_type = match definitions.get_op(block, Op::TypeStruct) {
+        Some(block_type) => block_type,
+        None => return Ok(Vec::new()),
+    };
+
+    (0..block_type.operands.len())
+        .map(|index| {
+            let member = index as u32;
+            let decoration = |decoration| decorations.member(block, member, decoration);
+
+            Ok(InterfaceMember {
+                index: member,
+                name: member_names.get(&(block, member)).cloned(),
+                type_name: definitions.name(block_type.id_ref(index)?),
+                location: decorations.member_literal(block, member, Decoration::Location),
+                component: decorations.member_literal(block, member, Decoration::Component),
+                built_in: built_in(decoration(Decoration::BuiltIn)),
+                qualifiers: qualifiers(decoration),
+            })
+        })
+        .collect()
+}
+
+fn built_in(operands: Option<&[Operand]>) -> Option<BuiltIn> {
+    match operands?.first()? {
+        Operand::BuiltIn(built_in) => Some(*built_in),
+        _ => None,
+    }
+}
+
+fn qualifiers<'d>(decoration: impl Fn(Decoration) -> Option<&'d [Operand]>) -> Vec<Decoration> {
+    QUALIFIERS
+        .iter()
+        .copied()
+        .filter(|&qualifier| decoration(qualifier).is_some())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assembler::assemble, module_info::ModuleInfo};
+
+    const RESOURCES: &str = r#"
+               OpCapability Shader
+               OpCapability RayQueryKHR
+               OpCapability InputAttachment
+               OpMemoryModel Logical GLSL450
+               OpName %ubo "ubo"
+               OpName %ssbo "ssbo"
+               OpName %buffer_block "buffer_block"
+               OpName %texture "texture"
+               OpName %image "image"
+               OpName %texels "texels"
+               OpName %storage_texels "storage_texels"
+               OpName %sampler "linear"
+               OpName %combined "combined"
+               OpName %scene "scene"
+               OpName %attachment "attachment"
+               OpName %textures "textures"
+               OpName %bindless "bindless"
+               OpName %count "count"
+               OpName %sized "sized"
+               OpDecorate %UBO Block
+               OpDecorate %SSBO Block
+               OpDecorate %OldSSBO BufferBlock
+               OpMemberDecorate %UBO 0 Offset 0
+               OpMemberDecorate %SSBO 0 Offset 0
+               OpMemberDecorate %OldSSBO 0 Offset 0
+               OpDecorate %count SpecId 0
+               OpDecorate %ubo DescriptorSet 0
+               OpDecorate %ubo Binding 0
+               OpDecorate %ssbo DescriptorSet 0
+               OpDecorate %ssbo Binding 1
+               OpDecorate %buffer_block DescriptorSet 0
+               OpDecorate %buffer_block Binding 2
+               OpDecorate %texture DescriptorSet 1
+               OpDecorate %texture Binding 0
+               OpDecorate %image DescriptorSet 1
+               OpDecorate %image Binding 1
+               OpDecorate %texels DescriptorSet 1
+               OpDecorate %texels Binding 2
+               OpDecorate %storage_texels DescriptorSet 1
+               OpDecorate %storage_texels Binding 3
+               OpDecorate %sampler DescriptorSet 2
+               OpDecorate %sampler Binding 0
+               OpDecorate %combined DescriptorSet 2
+               OpDecorate %combined Binding 1
+               OpDecorate %scene DescriptorSet 3
+               OpDecorate %scene Binding 0
+               OpDecorate %attachment DescriptorSet 3
+               OpDecorate %attachment Binding 1
+               OpDecorate %attachment InputAttachmentIndex 0
+               OpDecorate %textures DescriptorSet 4
+               OpDecorate %textures Binding 0
+               OpDecorate %bindless DescriptorSet 4
+               OpDecorate %bindless Binding 1
+               OpDecorate %sized DescriptorSet 4
+               OpDecorate %sized Binding 2
+      %float = OpTypeFloat 32
+       %uint = OpTypeInt 32 0
+     %uint_4 = OpConstant %uint 4
+      %count = OpSpecConstant %uint 8
+        %UBO = OpTypeStruct %float
+       %SSBO = OpTypeStruct %float
+    %OldSSBO = OpTypeStruct %float
+      %tex2D = OpTypeImage %float 2D 0 0 0 1 Unknown
+      %img3D = OpTypeImage %float 3D 0 0 0 2 Rgba16f
+   %texel_bf = OpTypeImage %float Buffer 0 0 0 1 Unknown
+ %storage_bf = OpTypeImage %float Buffer 0 0 0 2 R32f
+    %subpass = OpTypeImage %float SubpassData 0 0 0 2 Unknown
+  %sampler_t = OpTypeSampler
+ %combined_t = OpTypeSampledImage %tex2D
+      %accel = OpTypeAccelerationStructureKHR
+   %tex_x4_t = OpTypeArray %tex2D %uint_4
+   %tex_rt_t = OpTypeRuntimeArray %combined_t
+   %tex_sc_t = OpTypeArray %tex2D %count
+      %p_ubo = OpTypePointer Uniform %UBO
+     %p_ssbo = OpTypePointer StorageBuffer %SSBO
+  %p_old_sb = OpTypePointer Uniform %OldSSBO
+    %p_tex2D = OpTypePointer UniformConstant %tex2D
+    %p_img3D = OpTypePointer UniformConstant %img3D
+    %p_texel = OpTypePointer UniformConstant %texel_bf
+  %p_storage = OpTypePointer UniformConstant %storage_bf
+  %p_subpass = OpTypePointer UniformConstant %subpass
+  %p_sampler = OpTypePointer UniformConstant %sampler_t
+ %p_combined = OpTypePointer UniformConstant %combined_t
+    %p_accel = OpTypePointer UniformConstant %accel
+   %p_tex_x4 = OpTypePointer UniformConstant %tex_x4_t
+   %p_tex_rt = OpTypePointer UniformConstant %tex_rt_t
+   %p_tex_sc = OpTypePointer UniformConstant %tex_sc_t
+        %ubo = OpVariable %p_ubo Uniform
+       %ssbo = OpVariable %p_ssbo StorageBuffer
+%buffer_block = OpVariable %p_old_sb Uniform
+    %texture = OpVariable %p_tex2D UniformConstant
+      %image = OpVariable %p_img3D UniformConstant
+     %texels = OpVariable %p_texel UniformConstant
+%storage_texels = OpVariable %p_storage UniformConstant
+    %sampler = OpVariable %p_sampler UniformConstant
+   %combined = OpVariable %p_combined UniformConstant
+      %scene = OpVariable %p_accel UniformConstant
+ %attachment = OpVariable %p_subpass UniformConstant
+   %textures = OpVariable %p_tex_x4 UniformConstant
+   %bindless = OpVariable %p_tex_rt UniformConstant
+      %sized = OpVariable %p_tex_sc UniformConstant
+"#;
+
+    fn resources(text: &str) -> Vec<Resource> {
+        let module = assemble(text).unwrap();
+        ModuleInfo::create(&module).unwrap().resources
+    }
+
+    #[test]
+    fn descriptor_types() {
+        let descriptor_types = resources(RESOURCES)
+            .into_iter()
+            .map(|resource| (resource.name.unwrap(), resource.descriptor_type))
+            .collect::<Vec<_>>();
+
+        let expected = [
+            ("ubo", DescriptorType::UniformBuffer),
+            ("ssbo", DescriptorType::StorageBuffer),
+            ("buffer_block", DescriptorType::StorageBuffer),
+            ("texture", DescriptorType::SampledImage),
+            ("image", DescriptorType::StorageImage),
+            ("texels", DescriptorType::UniformTexelBuffer),
+            ("storage_texels", DescriptorType::StorageTexelBuffer),
+            ("linear", DescriptorType::Sampler),
+            ("combined", DescriptorType::CombinedImageSampler),
+            ("scene", DescriptorType::AccelerationStructure),
+            ("attachment", DescriptorType::InputAttachment),
+            ("textures", DescriptorType::SampledImage),
+            ("bindless", DescriptorType::CombinedImageSampler),
+            ("sized", DescriptorType::SampledImage),
+        ]
+        .map(|(name, descriptor_type)| (name.to_string(), Some(descriptor_type)));
+        assert_eq!(descriptor_types, expected);
+    }
+
+    #[test]
+    fn sets_bindings_and_array_sizes() {
+        let bindings = resources(RESOURCES)
+            .into_iter()
+            .map(|resource| (resource.set, resource.binding, resource.array_size))
+            .collect::<Vec<_>>();
+
+        let single = |set, binding| (Some(set), Some(binding), None);
+        assert_eq!(
+            bindings,
+            [
+                single(0, 0),
+                single(0, 1),
+                single(0, 2),
+                single(1, 0),
+                single(1, 1),
+                single(1, 2),
+                single(1, 3),
+                single(2, 0),
+                single(2, 1),
+                single(3, 0),
+                single(3, 1),
+                (Some(4), Some(0), Some(ArraySize::Constant(4))),
+                (Some(4), Some(1), Some(ArraySize::Runtime)),
+                (
+                    Some(4),
+                    Some(2),
+                    Some(ArraySize::SpecConstant("count(id=0,default=8)".to_string()))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn element_types_of_arrays() {
+        let resources = resources(RESOURCES);
+        let type_name = |name: &str| {
+            resources
+                .iter()
+                .find(|resource| resource.name.as_deref() == Some(name))
+                .map(|resource| resource.type_name.clone())
+                .unwrap()
+        };
+        assert_eq!(type_name("textures"), type_name("texture"));
+        assert_eq!(type_name("bindless"), type_name("combined"));
+    }
+
+    #[test]
+    fn variables_without_descriptors() {
+        let resources = resources(
+            r#"
+               OpCapability Shader
+               OpMemoryModel Logical GLSL450
+      %float = OpTypeFloat 32
+    %p_float = OpTypePointer UniformConstant %float
+      %loose = OpVariable %p_float UniformConstant
+   %p_input = OpTypePointer Input %float
+      %input = OpVariable %p_input Input
+"#,
+        );
+
+        // OpenGL uniforms outside of blocks, without a set or binding
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].set, None);
+        assert_eq!(resources[0].binding, None);
+        assert_eq!(resources[0].descriptor_type, None);
+    }
+
+    const INTERFACE: &str = r#"
+               OpCapability Shader
+               OpMemoryModel Logical GLSL450
+               OpEntryPoint Vertex %main "main" %per_vertex %v_w %vertex_index %v_color %normal %v_uv %position
+               OpName %position "position"
+               OpName %normal "normal"
+               OpName %vertex_index "vertex_index"
+               OpName %per_vertex "per_vertex"
+               OpName %v_color "v_color"
+               OpName %v_uv "v_uv"
+               OpName %v_w "v_w"
+               OpName %PerVertex "gl_PerVertex"
+               OpMemberName %PerVertex 0 "gl_Position"
+               OpMemberName %PerVertex 1 "gl_PointSize"
+               OpDecorate %position Location 0
+               OpDecorate %normal Location 1
+               OpDecorate %vertex_index BuiltIn VertexIndex
+               OpDecorate %PerVertex Block
+               OpMemberDecorate %PerVertex 0 BuiltIn Position
+               OpMemberDecorate %PerVertex 1 BuiltIn PointSize
+               OpMemberDecorate %PerVertex 1 Invariant
+               OpDecorate %v_color Location 1
+               OpDecorate %v_color Flat
+               OpDecorate %v_uv Location 0
+               OpDecorate %v_uv Component 0
+               OpDecorate %v_uv NoPerspective
+               OpDecorate %v_w Location 0
+               OpDecorate %v_w Component 2
+       %void = OpTypeVoid
+    %fn_void = OpTypeFunction %void
+      %float = OpTypeFloat 32
+        %int = OpTypeInt 32 1
+       %uint = OpTypeInt 32 0
+       %vec2 = OpTypeVector %float 2
+       %vec3 = OpTypeVector %float 3
+       %vec4 = OpTypeVector %float 4
+      %uvec4 = OpTypeVector %uint 4
+  %PerVertex = OpTypeStruct %vec4 %float
+   %in_vec3 = OpTypePointer Input %vec3
+    %in_int = OpTypePointer Input %int
+%out_block = OpTypePointer Output %PerVertex
+%out_uvec4 = OpTypePointer Output %uvec4
+ %out_vec2 = OpTypePointer Output %vec2
+%out_float = OpTypePointer Output %float
+   %position = OpVariable %in_vec3 Input
+     %normal = OpVariable %in_vec3 Input
+%vertex_index = OpVariable %in_int Input
+ %per_vertex = OpVariable %out_block Output
+    %v_color = OpVariable %out_uvec4 Output
+       %v_uv = OpVariable %out_vec2 Output
+        %v_w = OpVariable %out_float Output
+       %main = OpFunction %void None %fn_void
+      %entry = OpLabel
+               OpReturn
+               OpFunctionEnd
+"#;
+
+    fn interface(text: &str) -> Vec<InterfaceVariable> {
+        let module = assemble(text).unwrap();
+        let mut entry_points = ModuleInfo::create(&module).unwrap().entry_points;
+        assert_eq!(entry_points.len(), 1);
+        entry_points.remove(0).interface
+    }
+
+    #[test]
+    fn interface_is_sorted_by_storage_class_and_location() {
+        let order = interface(INTERFACE)
+            .into_iter()
+            .map(|variable| {
+                let name = variable.name.unwrap();
+                (
+                    name,
+                    variable.storage_class,
+                    variable.location,
+                    variable.component,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let expected = [
+            ("position", StorageClass::Input, Some(0), None),
+            ("normal", StorageClass::Input, Some(1), None),
+            ("vertex_index", StorageClass::Input, None, None),
+            ("v_uv", StorageClass::Output, Some(0), Some(0)),
+            ("v_w", StorageClass::Output, Some(0), Some(2)),
+            ("v_color", StorageClass::Output, Some(1), None),
+            ("per_vertex", StorageClass::Output, None, None),
+        ]
+        .map(|(name, storage_class, location, component)| {
+            (name.to_string(), storage_class, location, component)
+        });
+        assert_eq!(order, expected);
+    }
+
+    #[test]
+    fn interface_decorations() {
+        let interface = interface(INTERFACE);
+        let variable = |name: &str| {
+            interface
+                .iter()
+                .find(|variable| variable.name.as_deref() == Some(name))
+                .unwrap()
+        };
+
+        assert_eq!(
+            variable("vertex_index").built_in,
+            Some(BuiltIn::VertexIndex)
+        );
+        assert_eq!(variable("position").built_in, None);
+        assert_eq!(variable("v_color").qualifiers, [Decoration::Flat]);
+        assert_eq!(variable("v_uv").qualifiers, [Decoration::NoPerspective]);
+        assert!(variable("v_w").qualifiers.is_empty());
+        assert!(variable("v_color").members.is_empty());
+    }
+
+    #[test]
+    fn interface_block_members() {
+        let interface = interface(INTERFACE);
+        let block = interface
+            .iter()
+            .find(|variable| variable.name.as_deref() == Some("per_vertex"))
+            .unwrap();
+
+        assert_eq!(block.built_in, None);
+        let members = block
+            .members
+            .iter()
+            .map(|member| {
+                (
+                    member.index,
+                    member.name.as_deref(),
+                    member.built_in,
+                    member.qualifiers.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            [
+                (0, Some("gl_Position"), Some(BuiltIn::Position), vec![]),
+                (
+                    1,
+                    Some("gl_PointSize"),
+                    Some(BuiltIn::PointSize),
+                    vec![Decoration::Invariant]
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use crate::{
+    assembler::{self, NumberType},
+    options::OptimizationLevel,
+    CompileError,
+};
+use rspirv::{
+    binary::Assemble,
+    dr::{Module, Operand},
+    grammar::CoreInstructionTable,
+};
+use spirv::{Decoration, Op, Word};
+use spirv_tools::{
+    error::{Message, MessageLevel},
+    opt::{self, Optimizer, Passes},
+    TargetEnv,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Applies specialization constant overrides to a compiled module.
+///
+/// The overridden constants are frozen into regular constants and everything
+/// that only depends on constants is folded, like spirv-opt's
+/// `--set-spec-const-default-value` followed by `--freeze-spec-const` and
+/// `--fold-spec-const-op-composite`. The passes of the optimization level then
+/// propagate the new values into the code.
+pub fn specialize(
+    module: &Module,
+    overrides: &BTreeMap<u32, String>,
+    optimization: OptimizationLevel,
+    env: Option<TargetEnv>,
+) -> Result<Vec<u32>, CompileError> {
+    let mut module = module.clone();
+    freeze(&mut module, overrides).map_err(CompileError::InvalidOptions)?;
+
+    let mut optimizer = opt::create(env);
+    optimizer.register_pass(Passes::FoldSpecConstantOpAndComposite);
+    match optimization {
+        OptimizationLevel::Zero => (),
+        OptimizationLevel::Size => {
+            optimizer.register_size_passes();
+        },
+        OptimizationLevel::Performance => {
+            optimizer.register_performance_passes();
+        },
+    }
+
+    let mut errors = Vec::new();
+    let result = optimizer.optimize(
+        module.assemble(),
+        &mut |message: Message| match message.level {
+            MessageLevel::Fatal | MessageLevel::InternalError | MessageLevel::Error => {
+                errors.push(message.message)
+            },
+            MessageLevel::Warning | MessageLevel::Info | MessageLevel::Debug => (),
+        },
+        None,
+    );
+
+    match result {
+        Ok(binary) => Ok(binary.as_words().to_vec()),
+        Err(e) => {
+            errors.insert(0, format!("Failed to specialize the module: {e}"));
+            Err(CompileError::Specialization(errors.join("\n")))
+        },
+    }
+}
+
+/// Turns the specialization constants with an overridden `SpecId` into regular
+/// constants with the override as their value
+pub fn freeze(module: &mut Module, overrides: &BTreeMap<u32, String>) -> Result<(), String> {
+    let spec_ids = module
+        .annotations
+        .iter()
+        .filter(|instruction| is_spec_id(instruction))
+        .filter_map(|instruction| match instruction.operands.as_slice() {
+            [Operand::IdRef(target), _, Operand::LiteralBit32(spec_id)] => {
+                Some((*target, *spec_id))
+            },
+            _ => None,
+        })
+        .collect::<HashMap<Word, u32>>();
+
+    let number_types = module
+        .types_global_values
+        .iter()
+        .filter_map(|instruction| {
+            let number_type = match (instruction.class.opcode, instruction.operands.as_slice()) {
+                (Op::TypeInt, [Operand::LiteralBit32(width), Operand::LiteralBit32(signed)]) => {
+                    NumberType::Int {
+                        width: *width,
+                        signed: *signed != 0,
+                    }
+                },
+                (Op::TypeFloat, [Operand::LiteralBit32(width), ..]) => {
+                    NumberType::Float { width: *width }
+                },
+                _ => return None,
+            };
+            Some((instruction.result_id?, number_type))
+        })
+        .collect::<HashMap<Word, NumberType>>();
+
+    let mut frozen = HashSet::new();
+    let mut frozen_spec_ids = HashSet::new();
+    for instruction in module.types_global_values.iter_mut() {
+        let (id, spec_id) = match instruction
+            .result_id
+            .and_then(|id| Some((id, *spec_ids.get(&id)?)))
+        {
+            Some(spec) => spec,
+            None => continue,
+        };
+        let value = match overrides.get(&spec_id) {
+            Some(value) => value.trim(),
+            None => continue,
+        };
+
+        match instruction.class.opcode {
+            Op::SpecConstantTrue | Op::SpecConstantFalse => {
+                let opcode = match value {
+                    "true" => Op::ConstantTrue,
+                    "false" => Op::ConstantFalse,
+                    _ => {
+                        return Err(format!(
+                            "SpecId {spec_id} is a bool and cannot be set to {value}"
+                        ))
+                    },
+                };
+                instruction.class = CoreInstructionTable::get(opcode);
+            },
+            Op::SpecConstant => {
+                let number_type = instruction
+                    .result_type
+                    .and_then(|result_type| number_types.get(&result_type))
+                    .copied()
+                    .ok_or_else(|| format!("SpecId {spec_id} is not a number"))?;
+                let literal = assembler::number_literal(value, number_type)
+                    .ok_or_else(|| format!("{value} is not a valid value for SpecId {spec_id}"))?;
+
+                instruction.class = CoreInstructionTable::get(Op::Constant);
+                instruction.operands = vec![literal];
+            },
+            _ => continue,
+        }
+
+        frozen.insert(id);
+        frozen_spec_ids.insert(spec_id);
+    }
+
+    if let Some(spec_id) = overrides
+        .keys()
+        .find(|spec_id| !frozen_spec_ids.contains(spec_id))
+    {
+        return Err(format!(
+            "The module has no specialization constant with SpecId {spec_id}"
+        ));
+    }
+
+    // SpecId may only decorate specialization constants
+    module.annotations.retain(|instruction| {
+        !(is_spec_id(instruction)
+            && matches!(instruction.operands.first(), Some(Operand::IdRef(target)) if frozen.contains(target)))
+    });
+
+    Ok(())
+}
+
+fn is_spec_id(instruction: &rspirv::dr::Instruction) -> bool {
+    instruction.class.opcode == Op::Decorate
+        && matches!(
+            instruction.operands.get(1),
+            Some(Operand::Decoration(Decoration::SpecId))
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::assemble;
+
+    const MODULE: &str = r#"; Version: 1.0
+               OpCapability Shader
+               OpMemoryModel Logical GLSL450
+               OpEntryPoint GLCompute %main "main"
+               OpExecutionMode %main LocalSize 1 1 1
+               OpDecorate %size SpecId 3
+               OpDecorate %flag SpecId 4
+               OpDecorate %other SpecId 5
+       %void = OpTypeVoid
+    %fn_void = OpTypeFunction %void
+       %bool = OpTypeBool
+        %int = OpTypeInt 32 1
+       %size = OpSpecConstant %int 16
+       %flag = OpSpecConstantTrue %bool
+      %other = OpSpecConstant %int 1
+        %two = OpConstant %int 2
+        %sum = OpSpecConstantOp %int IAdd %size %two
+       %main = OpFunction %void None %fn_void
+      %entry = OpLabel
+               OpReturn
+               OpFunctionEnd
+"#;
+
+    fn overrides(values: &[(u32, &str)]) -> BTreeMap<u32, String> {
+        values
+            .iter()
+            .map(|(spec_id, value)| (*spec_id, value.to_string()))
+            .collect()
+    }
+
+    fn spec_ids(module: &Module) -> Vec<u32> {
+        module
+            .annotations
+            .iter()
+            .filter(|instruction| is_spec_id(instruction))
+            .filter_map(|instruction| match instruction.operands.as_slice() {
+                [_, _, Operand::LiteralBit32(spec_id)] => Some(*spec_id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn opcodes(module: &Module) -> Vec<Op> {
+        module
+            .types_global_values
+            .iter()
+            .map(|instruction| instruction.class.opcode)
+            .collect()
+    }
+
+    #[test]
+    fn freezing_removes_the_spec_id() {
+        let mut module = assemble(MODULE).unwrap();
+        freeze(&mut module, &overrides(&[(3, "-4"), (4, "false")])).unwrap();
+
+        assert_eq!(spec_ids(&module), [5]);
+        assert_eq!(
+            opcodes(&module),
+            [
+                Op::TypeVoid,
+                Op::TypeFunction,
+                Op::TypeBool,
+                Op::TypeInt,
+                Op::Constant,
+                Op::ConstantFalse,
+                Op::SpecConstant,
+                Op::Constant,
+                Op::SpecConstantOp,
+            ]
+        );
+        assert_eq!(
+            module.types_global_values[4].operands,
+            [Operand::LiteralBit32(-4i32 as u32)]
+        );
+    }
+
+    #[test]
+    fn freezing_without_overrides_changes_nothing() {
+        let original = assemble(MODULE).unwrap();
+        let mut module = original.clone();
+        freeze(&mut module, &BTreeMap::new()).unwrap();
+        assert_eq!(module.assemble(), original.assemble());
+    }
+
+    #[test]
+    fn invalid_overrides() {
+        let freeze_error = |values: &[(u32, &str)]| {
+            let mut module = assemble(MODULE).unwrap();
+            freeze(&mut module, &overrides(values)).unwrap_err()
+        };
+
+        assert_eq!(
+            freeze_error(&[(9, "1")]),
+            "The module has no specialization constant with SpecId 9"
+        );
+        assert_eq!(
+            freeze_error(&[(3, "1.5")]),
+            "1.5 is not a valid value for SpecId 3"
+        );
+        assert_eq!(
+            freeze_error(&[(4, "1")]),
+            "SpecId 4 is a bool and cannot be set to 1"
+        );
+    }
+
+    #[test]
+    fn invalid_overrides_are_invalid_options() {
+        let module = assemble(MODULE).unwrap();
+        let result = specialize(
+            &module,
+            &overrides(&[(9, "1")]),
+            OptimizationLevel::Zero,
+            Some(TargetEnv::Universal_1_0),
+        );
+        assert!(matches!(result, Err(CompileError::InvalidOptions(_))));
+    }
+
+    #[test]
+    fn dependent_operations_are_folded() {
+        let module = assemble(MODULE).unwrap();
+        let words = specialize(
+            &module,
+            &overrides(&[(3, "40")]),
+            OptimizationLevel::Zero,
+            Some(TargetEnv::Universal_1_0),
+        )
+        .unwrap();
+        let specialized = rspirv::dr::load_words(words).unwrap();
+
+        assert_eq!(spec_ids(&specialized), [4, 5]);
+        assert!(!opcodes(&specialized).contains(&Op::SpecConstantOp));
+
+        let constants = specialized
+            .types_global_values
+            .iter()
+            .filter(|instruction| instruction.class.opcode == Op::Constant)
+            .map(|instruction| instruction.operands[0].clone())
+            .collect::<Vec<_>>();
+        assert!(constants.contains(&Operand::LiteralBit32(40)));
+        assert!(constants.contains(&Operand::LiteralBit32(42)));
+    }
+}
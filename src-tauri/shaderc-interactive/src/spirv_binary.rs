@@ -0,0 +1,100 @@
+use eyre::{bail, Result};
+use spirv::Word;
+
+pub const MAGIC_NUMBER: Word = 0x0723_0203;
+
+/// Splits a SPIR-V binary into words.
+///
+/// The byte order is detected from the magic number, so modules written on
+/// big endian machines load as well.
+pub fn words_from_bytes(bytes: &[u8]) -> Result<Vec<Word>> {
+    if bytes.len() % 4 != 0 {
+        bail!(
+            "SPIR-V binaries consist of 32 bit words, but {} bytes were given",
+            bytes.len()
+        );
+    }
+    if bytes.len() < 4 * 5 {
+        bail!(
+            "A SPIR-V module has a header of 5 words, but only {} bytes were given",
+            bytes.len()
+        );
+    }
+
+    let little_endian =
+        |chunk: &[u8]| Word::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    let big_endian = |chunk: &[u8]| Word::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+
+    let words = if little_endian(bytes) == MAGIC_NUMBER {
+        bytes.chunks_exact(4).map(little_endian).collect()
+    } else if big_endian(bytes) == MAGIC_NUMBER {
+        bytes.chunks_exact(4).map(big_endian).collect()
+    } else {
+        bail!(
+            "Not a SPIR-V module, the magic number is {:#010x} instead of {:#010x}",
+            little_endian(bytes),
+            MAGIC_NUMBER
+        );
+    };
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: [Word; 5] = [MAGIC_NUMBER, 0x0001_0300, 0, 8, 0];
+
+    #[test]
+    fn little_endian() {
+        let bytes = HEADER
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(words_from_bytes(&bytes).unwrap(), HEADER);
+    }
+
+    #[test]
+    fn big_endian() {
+        let bytes = HEADER
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(words_from_bytes(&bytes).unwrap(), HEADER);
+    }
+
+    #[test]
+    fn wrong_magic_number() {
+        let mut bytes = HEADER
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>();
+        bytes[0] = 0;
+        let error = words_from_bytes(&bytes).unwrap_err().to_string();
+        assert!(error.contains("magic number is 0x07230200"), "{error}");
+    }
+
+    #[test]
+    fn truncated_trailing_word() {
+        let mut bytes = HEADER
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>();
+        bytes.extend([1, 2, 3]);
+        let error = words_from_bytes(&bytes).unwrap_err().to_string();
+        assert!(error.contains("23 bytes"), "{error}");
+    }
+
+    #[test]
+    fn shorter_than_the_header() {
+        let bytes = MAGIC_NUMBER.to_le_bytes();
+        let error = words_from_bytes(&bytes).unwrap_err().to_string();
+        assert!(error.contains("header of 5 words"), "{error}");
+    }
+
+    #[test]
+    fn empty() {
+        assert!(words_from_bytes(&[]).is_err());
+    }
+}
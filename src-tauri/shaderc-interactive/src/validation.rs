@@ -0,0 +1,229 @@
+use crate::{
+    options::{TargetEnv, TargetEnvVersion},
+    CompileError,
+};
+use serde::{Deserialize, Serialize};
+use spirv_tools::val::{self, Validator};
+
+/// A validation error, located at an instruction where possible
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValidationMessage {
+    pub message: String,
+    /// Position of the offending instruction in the module, counting every
+    /// instruction in layout order
+    pub instruction: Option<usize>,
+}
+
+/// The environment a module is validated for
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidatorEnv {
+    Env(spirv_tools::TargetEnv),
+    /// Why the module cannot be validated, e.g. because the bundled SPIR-V
+    /// Tools predate Vulkan 1.3 and SPIR-V 1.6
+    Unsupported(String),
+}
+
+impl ValidatorEnv {
+    /// The environment for the optimizer, which knows the same ones
+    pub fn env(&self) -> Option<spirv_tools::TargetEnv> {
+        match self {
+            Self::Env(env) => Some(*env),
+            Self::Unsupported(_) => None,
+        }
+    }
+}
+
+/// The validation rules for a target environment. Without an environment only
+/// the universal rules of the module's SPIR-V version apply.
+///
+/// Modules with a newer SPIR-V version than the environment allows are not
+/// validated either, as the rules of the environment do not cover them.
+pub fn validator_env(
+    target: Option<(TargetEnv, TargetEnvVersion)>,
+    spirv_version: (u8, u8),
+) -> Result<ValidatorEnv, CompileError> {
+    use spirv_tools::TargetEnv as Env;
+
+    let (env, newest_version) = match (target, spirv_version) {
+        (Some((TargetEnv::Vulkan, TargetEnvVersion::V1_0)), _) => (Env::Vulkan_1_0, (1, 0)),
+        (Some((TargetEnv::Vulkan, TargetEnvVersion::V1_1)), (1, minor)) if minor >= 4 => {
+            (Env::Vulkan_1_1_Spirv_1_4, (1, 4))
+        },
+        (Some((TargetEnv::Vulkan, TargetEnvVersion::V1_1)), _) => (Env::Vulkan_1_1, (1, 3)),
+        (Some((TargetEnv::Vulkan, TargetEnvVersion::V1_2)), _) => (Env::Vulkan_1_2, (1, 5)),
+        (Some((TargetEnv::Vulkan, TargetEnvVersion::V1_3)), _) => {
+            return Ok(ValidatorEnv::Unsupported(
+                "Not validated, the bundled SPIR-V Tools do not support Vulkan 1.3".to_string(),
+            ))
+        },
+        (Some((TargetEnv::OpenGL, TargetEnvVersion::V4_5)), _) => (Env::OpenGL_4_5, (1, 0)),
+        (Some((target_env, version)), _) => {
+            return Err(CompileError::InvalidOptions(format!(
+                "Unknown {:?} version: {}",
+                target_env, version
+            )))
+        },
+
+        (None, (1, 0)) => (Env::Universal_1_0, (1, 0)),
+        (None, (1, 1)) => (Env::Universal_1_1, (1, 1)),
+        (None, (1, 2)) => (Env::Universal_1_2, (1, 2)),
+        (None, (1, 3)) => (Env::Universal_1_3, (1, 3)),
+        (None, (1, 4)) => (Env::Universal_1_4, (1, 4)),
+        (None, (1, 5)) => (Env::Universal_1_5, (1, 5)),
+        (None, (1, 6)) => {
+            return Ok(ValidatorEnv::Unsupported(
+                "Not validated, the bundled SPIR-V Tools do not support SPIR-V 1.6".to_string(),
+            ))
+        },
+        (None, (major, minor)) => {
+            return Err(CompileError::InvalidSpirv(format!(
+                "Unsupported SPIR-V version {major}.{minor}"
+            )))
+        },
+    };
+
+    Ok(match target {
+        Some((target_env, version)) if spirv_version > newest_version => {
+            ValidatorEnv::Unsupported(format!(
+                "Not validated, the rules of {:?} {} only cover SPIR-V up to {}.{}",
+                target_env, version, newest_version.0, newest_version.1
+            ))
+        },
+        _ => ValidatorEnv::Env(env),
+    })
+}
+
+/// The result of validating a module
+#[derive(Clone, Debug)]
+pub enum Validation {
+    /// Empty if the module is valid
+    Messages(Vec<ValidationMessage>),
+    /// Why the module was not validated
+    Skipped(String),
+}
+
+/// Runs the SPIR-V Tools validator.
+///
+/// The validator stops at the first error, so at most one message is returned.
+pub fn validate(words: &[u32], env: &ValidatorEnv) -> Validation {
+    let env = match env {
+        ValidatorEnv::Env(env) => *env,
+        ValidatorEnv::Unsupported(reason) => return Validation::Skipped(reason.clone()),
+    };
+    let validator = val::create(Some(env));
+
+    Validation::Messages(match validator.validate(words, None) {
+        Ok(()) => Vec::new(),
+        Err(e) => vec![match e.diagnostic {
+            // The index is the 1-based position of the instruction, or 0 if
+            // the error is not about a single instruction
+            Some(diagnostic) => ValidationMessage {
+                message: diagnostic.message,
+                instruction: diagnostic.index.checked_sub(1),
+            },
+            None => ValidationMessage {
+                message: e.to_string(),
+                instruction: None,
+            },
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assemble_spirv, options::LoadOptions};
+    use spirv_tools::TargetEnv as Env;
+
+    const VULKAN_1_2: Option<(TargetEnv, TargetEnvVersion)> =
+        Some((TargetEnv::Vulkan, TargetEnvVersion::V1_2));
+
+    #[test]
+    fn environments_for_supported_versions() {
+        let vulkan_1_1 = Some((TargetEnv::Vulkan, TargetEnvVersion::V1_1));
+        let env = |target, spirv_version| validator_env(target, spirv_version).unwrap();
+
+        assert_eq!(env(VULKAN_1_2, (1, 5)), ValidatorEnv::Env(Env::Vulkan_1_2));
+        assert_eq!(env(vulkan_1_1, (1, 3)), ValidatorEnv::Env(Env::Vulkan_1_1));
+        assert_eq!(
+            env(vulkan_1_1, (1, 4)),
+            ValidatorEnv::Env(Env::Vulkan_1_1_Spirv_1_4)
+        );
+        assert_eq!(env(None, (1, 5)), ValidatorEnv::Env(Env::Universal_1_5));
+    }
+
+    #[test]
+    fn spirv_versions_newer_than_the_environment_are_not_validated() {
+        assert_eq!(
+            validator_env(VULKAN_1_2, (1, 6)).unwrap(),
+            ValidatorEnv::Unsupported(
+                "Not validated, the rules of Vulkan 1.2 only cover SPIR-V up to 1.5".to_string()
+            )
+        );
+
+        let vulkan_1_0 = Some((TargetEnv::Vulkan, TargetEnvVersion::V1_0));
+        let opengl = Some((TargetEnv::OpenGL, TargetEnvVersion::V4_5));
+        assert!(matches!(
+            validator_env(vulkan_1_0, (1, 3)),
+            Ok(ValidatorEnv::Unsupported(_))
+        ));
+        assert!(matches!(
+            validator_env(opengl, (1, 5)),
+            Ok(ValidatorEnv::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn vulkan_1_3_is_not_validated() {
+        let vulkan_1_3 = Some((TargetEnv::Vulkan, TargetEnvVersion::V1_3));
+        for spirv_version in [(1, 0), (1, 6)] {
+            assert!(matches!(
+                validator_env(vulkan_1_3, spirv_version),
+                Ok(ValidatorEnv::Unsupported(_))
+            ));
+        }
+    }
+
+    fn vulkan(target_env_version: TargetEnvVersion) -> LoadOptions {
+        LoadOptions {
+            target_env: Some(TargetEnv::Vulkan),
+            target_env_version: Some(target_env_version),
+            ..LoadOptions::default()
+        }
+    }
+
+    #[test]
+    fn skipped_validation_is_shown() {
+        // A duplicate type, which the validator always reports
+        let text = |version: &str| {
+            format!(
+                "; Version: {version}
+                 OpCapability Shader
+                 OpMemoryModel Logical GLSL450
+            %1 = OpTypeInt 32 1
+            %2 = OpTypeInt 32 1"
+            )
+        };
+
+        for (version, target_env_version) in [
+            ("1.6", TargetEnvVersion::V1_2),
+            ("1.5", TargetEnvVersion::V1_3),
+        ] {
+            let loaded = assemble_spirv(&text(version), &vulkan(target_env_version)).unwrap();
+            assert!(loaded.assembly.validation_skipped.is_some());
+            assert!(loaded.assembly.validation_errors.is_empty());
+        }
+
+        // Supported environments still report it
+        let loaded = assemble_spirv(&text("1.5"), &vulkan(TargetEnvVersion::V1_2)).unwrap();
+        assert_eq!(loaded.assembly.validation_skipped, None);
+        let errors = loaded.assembly.validation_errors.len()
+            + loaded
+                .assembly
+                .instructions
+                .iter()
+                .map(|instruction| instruction.validation_errors.len())
+                .sum::<usize>();
+        assert_eq!(errors, 1);
+    }
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use shaderc_interactive::{
+    compilation::Compilation,
+    diff::{CompileRequest, InstructionDiff},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct ShaderDiff {
+    pub left: Compilation,
+    pub right: Compilation,
+    /// Empty unless both shaders compiled
+    pub instructions: Vec<InstructionDiff>,
+}
+
+#[tauri::command]
+pub fn diff_shaders(left: CompileRequest, right: CompileRequest) -> ShaderDiff {
+    let diff = shaderc_interactive::diff::diff_shaders(&left, &right);
+
+    ShaderDiff {
+        left: Compilation::new(diff.left, &left.options),
+        right: Compilation::new(diff.right, &right.options),
+        instructions: diff.instructions,
+    }
+}
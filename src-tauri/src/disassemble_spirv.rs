@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use shaderc_interactive::{
+    annotated_disassembly::AnnotatedDisassembly, diagnostics::Diagnostics, options::LoadOptions,
+    CompileError, LoadedModule,
+};
+
+/// Where to read an existing SPIR-V module from
+#[derive(Serialize, Deserialize)]
+pub enum SpirvInput {
+    Bytes(Vec<u8>),
+    Path(String),
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum Disassembly {
+    Success {
+        assembly: AnnotatedDisassembly,
+        spirv_version: String,
+    },
+    Failure {
+        error: String,
+        /// Plain disassembly, if the module could at least be parsed
+        disassembly: Option<String>,
+        /// Parse errors with line numbers when assembling text
+        diagnostics: Diagnostics,
+    },
+}
+
+impl Disassembly {
+    pub fn new(result: Result<LoadedModule, CompileError>) -> Self {
+        match result {
+            Ok(loaded) => {
+                let (major, minor) = loaded.spirv_version;
+
+                Self::Success {
+                    assembly: loaded.assembly,
+                    spirv_version: format!("{major}.{minor}"),
+                }
+            },
+            Err(e) => Self::Failure {
+                disassembly: match &e {
+                    CompileError::Analysis { disassembly, .. } => disassembly.clone(),
+                    _ => None,
+                },
+                diagnostics: e.diagnostics(),
+                error: e.to_string(),
+            },
+        }
+    }
+}
+
+#[tauri::command]
+pub fn disassemble_spirv(input: SpirvInput, options: LoadOptions) -> Disassembly {
+    let bytes = match input {
+        SpirvInput::Bytes(bytes) => bytes,
+        SpirvInput::Path(path) => match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                let error = format!("Failed to read {path}: {e}");
+                return Disassembly::Failure {
+                    diagnostics: Diagnostics::error(&error),
+                    error,
+                    disassembly: None,
+                };
+            },
+        },
+    };
+
+    Disassembly::new(shaderc_interactive::disassemble_spirv(&bytes, &options))
+}
+
+#[tauri::command]
+pub fn assemble_spirv(text: &str, options: LoadOptions) -> Disassembly {
+    Disassembly::new(shaderc_interactive::assemble_spirv(text, &options))
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use shaderc_interactive::{compile_shader, diff::CompileRequest, host_structs::HostLanguage};
+
+#[derive(Serialize, Deserialize)]
+pub enum HostStructs {
+    Success { code: String },
+    Failure { error: String },
+}
+
+/// Compiles a shader and generates host-side definitions of its blocks
+#[tauri::command]
+pub fn generate_host_structs(request: CompileRequest, language: HostLanguage) -> HostStructs {
+    let compiled = match compile_shader(&request.source, request.shader_kind, &request.options) {
+        Ok(compiled) => compiled,
+        Err(e) => {
+            return HostStructs::Failure {
+                error: e.to_string(),
+            }
+        },
+    };
+
+    match shaderc_interactive::host_structs::host_structs(
+        &compiled.module,
+        &compiled.assembly.info,
+        language,
+    ) {
+        Ok(code) => HostStructs::Success { code },
+        Err(e) => HostStructs::Failure {
+            error: format!("{e:#}"),
+        },
+    }
+}
@@ -4,14 +4,29 @@
 )]
 
 pub mod compile_shader;
+pub mod diff_shaders;
+pub mod disassemble_spirv;
+pub mod host_structs;
+pub mod pass_pipeline;
 
 use compile_shader::compile_shader;
+use diff_shaders::diff_shaders;
+use disassemble_spirv::{assemble_spirv, disassemble_spirv};
+use host_structs::generate_host_structs;
+use pass_pipeline::run_passes;
 
 fn main() {
     color_eyre::install().unwrap();
 
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![compile_shader])
+        .invoke_handler(tauri::generate_handler![
+            compile_shader,
+            disassemble_spirv,
+            assemble_spirv,
+            run_passes,
+            diff_shaders,
+            generate_host_structs
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
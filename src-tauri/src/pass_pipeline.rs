@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use shaderc_interactive::{
+    compilation::Compilation,
+    options::{CompileShaderOptions, OptimizerPass, ShaderKind},
+    pass_pipeline::{PassError, PassStep},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct PassPipeline {
+    /// The module before any pass ran
+    pub compilation: Compilation,
+    pub steps: Vec<PassStep>,
+    pub error: Option<PassError>,
+}
+
+#[tauri::command]
+pub fn run_passes(
+    source: &str,
+    shader_kind: ShaderKind,
+    options: CompileShaderOptions,
+    passes: Vec<OptimizerPass>,
+) -> PassPipeline {
+    match shaderc_interactive::pass_pipeline::run_passes(source, shader_kind, &options, &passes) {
+        Ok(pipeline) => PassPipeline {
+            compilation: Compilation::new(Ok(pipeline.initial), &options),
+            steps: pipeline.steps,
+            error: pipeline.error,
+        },
+        Err(e) => PassPipeline {
+            compilation: Compilation::new(Err(e), &options),
+            steps: Vec::new(),
+            error: None,
+        },
+    }
+}
@@ -2,16 +2,40 @@ import React, { useRef, useState } from 'react';
 import './App.css';
 import {
     AnnotatedDisassembly,
+    assembleSpirv,
     compileShader,
+    compileShaderIsAnalysisFailure,
     compileShaderIsSuccess,
+    CompileRequest,
     CompileShaderOptions,
+    CompileShaderResult,
+    Diagnostic,
+    diffShaders,
+    disassembleSpirv,
+    disassemblyIsSuccess,
+    formatArraySize,
+    generateHostStructs,
+    HostLanguage,
+    isSourceFileLine,
     LineAnnotation,
+    LoadOptions,
+    MacroDefinitions,
+    OptimizationLevel,
+    OptimizerPasses,
+    parseMacroDefinitions,
+    PassStep,
+    runPasses,
     ShaderKind,
     ShaderKindCompute,
     ShaderKindMesh,
     ShaderKindRaster,
     ShaderKindRay,
+    Severity,
+    SourceLanguage,
+    Specialization,
+    SpirvVersions,
     TargetEnv,
+    TargetEnvVersions,
 } from './lib/shaderc';
 import Editor, { Monaco } from '@monaco-editor/react';
 import * as monaco from 'monaco-editor';
@@ -27,12 +51,34 @@ function App() {
     const [shaderKind, setShaderKind] = useState<ShaderKind>(
         ShaderKindRaster.Vertex,
     );
+    const [sourceLanguage, setSourceLanguage] = useState(SourceLanguage.GLSL);
     const [targetEnv, setTargetEnv] = useState(TargetEnv.Vulkan);
+    const [targetEnvVersion, setTargetEnvVersion] = useState('');
+    const [spirvVersion, setSpirvVersion] = useState('');
+    const [optimization, setOptimization] = useState(OptimizationLevel.Zero);
+    const [editingAssembly, setEditingAssembly] = useState(false);
 
     // Response data by the backend
     const [assembly, setAssembly] = useState<AnnotatedDisassembly | null>(null);
     const [error, setError] = useState('');
     const [warning, setWarning] = useState('');
+    const [activeMacros, setActiveMacros] = useState<MacroDefinitions>({});
+    const [activeVersions, setActiveVersions] = useState('');
+    const [passPipeline, setPassPipeline] = useState<{
+        initial: AnnotatedDisassembly;
+        steps: Array<PassStep>;
+    } | null>(null);
+    // The number of passes applied to the shown disassembly
+    const [passStep, setPassStep] = useState(0);
+    const [passError, setPassError] = useState('');
+    // A diff replaces the disassembly until the next compilation
+    const [diffView, setDiffView] = useState<{
+        text: string;
+        decorations: Array<[number, string]>;
+    } | null>(null);
+    const lastCompileRequest = useRef<CompileRequest | null>(null);
+    const [hostStructs, setHostStructs] = useState('');
+    const [specialization, setSpecialization] = useState<Specialization>({});
 
     // We decorate line matches in the editors. These are the decoration ids
     const disassemblyDecorationIds = useRef<Array<string>>([]);
@@ -50,6 +96,8 @@ function App() {
 
     const paddingLengthLimit = useRef<null | HTMLInputElement>(null);
     const entryPoint = useRef<null | HTMLInputElement>(null);
+    const macroDefinitions = useRef<null | HTMLInputElement>(null);
+    const passList = useRef<null | HTMLInputElement>(null);
 
     const editorDisassemblyPositionChanged = () => {
         const position = editorDisassemblyRef.current?.getPosition();
@@ -64,10 +112,10 @@ function App() {
             return;
         }
 
-        const sourceLineNumber = instruction.line?.line;
-        if (sourceLineNumber === undefined) {
+        if (!instruction.line || !isSourceFileLine(assembly, instruction.line)) {
             return;
         }
+        const sourceLineNumber = instruction.line.line;
         editorSourceRef.current?.revealLineInCenter(sourceLineNumber);
 
         setHighlightedLine(sourceLineNumber);
@@ -101,7 +149,10 @@ function App() {
         for (const [assemblyLineNumber, instruction] of Array.from(
             assembly.instructions.entries(),
         )) {
-            if (instruction.line?.line === sourceLineNumber) {
+            if (
+                instruction.line?.line === sourceLineNumber &&
+                isSourceFileLine(assembly, instruction.line)
+            ) {
                 disassemblyEditor.revealLineInCenter(assemblyLineNumber + 1);
                 break;
             }
@@ -147,7 +198,10 @@ function App() {
 
         for (let line = 0; line < assembly.instructions.length; line++) {
             const instruction = assembly.instructions[line];
-            if (instruction.line) {
+            if (
+                instruction.line &&
+                isSourceFileLine(assembly, instruction.line)
+            ) {
                 const thisDecorationKey = JSON.stringify(instruction.line);
 
                 // If this is a new source line, add a new decoration id
@@ -174,6 +228,29 @@ function App() {
         }
     }
 
+    // Mark what the selected pass did
+    const shownPassStep =
+        passPipeline && passStep > 0 ? passPipeline.steps[passStep - 1] : null;
+    if (shownPassStep) {
+        const passDecoration = (line: number, className: string) => ({
+            range: new monaco.Range(line + 1, 1, line + 1, 1),
+            options: { isWholeLine: true, className },
+        });
+        shownPassStep.added.forEach(line =>
+            disassemblyDecorations.push(passDecoration(line, 'pass-added')),
+        );
+        shownPassStep.changed.forEach(line =>
+            disassemblyDecorations.push(passDecoration(line, 'pass-changed')),
+        );
+    }
+
+    for (const [line, className] of diffView?.decorations ?? []) {
+        disassemblyDecorations.push({
+            range: new monaco.Range(line + 1, 1, line + 1, 1),
+            options: { isWholeLine: true, className },
+        });
+    }
+
     const sourceDecorations: Array<monaco.editor.IModelDeltaDecoration> = [];
     for (const [key, id] of Object.entries(decorationsByLineAnnotation)) {
         const lineAnnotation: LineAnnotation = JSON.parse(key);
@@ -211,38 +288,417 @@ function App() {
                 sourceDecorationIds.current,
                 sourceDecorations,
             ) || [];
+
+        const disassemblyModel = editorDisassemblyRef.current?.getModel();
+        if (disassemblyModel) {
+            monaco.editor.setModelMarkers(
+                disassemblyModel,
+                'spirv-val',
+                validationMarkers(disassemblyModel),
+            );
+        }
     });
 
-    const compile = async () => {
-        const options: CompileShaderOptions = { targetEnv };
-        const paddingLengthLimitCurrent = paddingLengthLimit.current?.value
+    // Underline instructions rejected by the SPIR-V validator
+    const validationMarkers = (
+        model: monaco.editor.ITextModel,
+    ): Array<monaco.editor.IMarkerData> => {
+        if (!assembly) {
+            return [];
+        }
+
+        return assembly.instructions.flatMap((instruction, line) =>
+            instruction.validation_errors.map(message => ({
+                severity: monaco.MarkerSeverity.Error,
+                message,
+                startLineNumber: line + 1,
+                startColumn: 1,
+                endLineNumber: line + 1,
+                endColumn: model.getLineMaxColumn(line + 1),
+            })),
+        );
+    };
+
+    // Module-level validation errors, or why the module was not validated, have
+    // no line to point at
+    const warningText = [
+        warning,
+        passError,
+        ...(shownPassStep?.messages ?? []),
+        ...(assembly?.validation_errors ?? []),
+        assembly?.validation_skipped,
+    ]
+        .filter(text => text)
+        .join('\n');
+
+    const diagnosticMarkers = (
+        model: monaco.editor.ITextModel,
+        diagnostics: Array<Diagnostic>,
+    ): Array<monaco.editor.IMarkerData> =>
+        diagnostics
+            .filter(diagnostic => diagnostic.line !== null)
+            .map(diagnostic => {
+                const line = Math.min(
+                    diagnostic.line ?? 1,
+                    model.getLineCount(),
+                );
+                return {
+                    severity:
+                        diagnostic.severity === Severity.Error
+                            ? monaco.MarkerSeverity.Error
+                            : monaco.MarkerSeverity.Warning,
+                    message: diagnostic.message,
+                    startLineNumber: line,
+                    startColumn: diagnostic.column ?? 1,
+                    endLineNumber: line,
+                    endColumn: diagnostic.column
+                        ? diagnostic.column + 1
+                        : model.getLineMaxColumn(line),
+                };
+            });
+
+    // Underline diagnostics in the source editor
+    const showDiagnostics = (
+        diagnostics: Array<Diagnostic>,
+        sourceFileName: string,
+    ) => {
+        const model = editorSourceRef.current?.getModel();
+        if (!model) {
+            return;
+        }
+
+        const markers = diagnosticMarkers(
+            model,
+            diagnostics.filter(
+                diagnostic => diagnostic.file === sourceFileName,
+            ),
+        );
+        monaco.editor.setModelMarkers(model, 'shaderc', markers);
+    };
+
+    // Modules that were not compiled here are validated for the selected
+    // environment
+    const loadOptions = (): LoadOptions => {
+        const options: LoadOptions = { targetEnv };
+        if (targetEnvVersion) {
+            options.targetEnvVersion = targetEnvVersion;
+        }
+        const limitResultNameLength = resultNameLengthLimit();
+        if (limitResultNameLength !== null) {
+            options.limitResultNameLength = limitResultNameLength;
+        }
+        return options;
+    };
+
+    const resultNameLengthLimit = () => {
+        const limit = paddingLengthLimit.current?.value
             ? Number(paddingLengthLimit.current?.value)
             : null;
-        if (
-            typeof paddingLengthLimitCurrent == 'number' &&
-            paddingLengthLimitCurrent >= 0
-        ) {
-            options.limitResultNameLength = Math.floor(
-                paddingLengthLimitCurrent,
-            );
+        return typeof limit == 'number' && limit >= 0
+            ? Math.floor(limit)
+            : null;
+    };
+
+    const sourceFileName =
+        sourceLanguage === SourceLanguage.HLSL ? 'shader.hlsl' : 'shader.glsl';
+
+    const compileOptions = (): CompileShaderOptions => {
+        const options: CompileShaderOptions = {
+            fileName: sourceFileName,
+            sourceLanguage,
+            targetEnv,
+            optimization,
+            macros: parseMacroDefinitions(
+                macroDefinitions.current?.value ?? '',
+            ),
+        };
+        if (targetEnvVersion) {
+            options.targetEnvVersion = targetEnvVersion;
+        }
+        if (spirvVersion) {
+            options.spirvVersion = spirvVersion;
+        }
+        const limitResultNameLength = resultNameLengthLimit();
+        if (limitResultNameLength !== null) {
+            options.limitResultNameLength = limitResultNameLength;
         }
         const entryPointValue = entryPoint.current?.value;
         if (entryPointValue) {
             options.entryPoint = entryPointValue;
         }
+        if (Object.keys(specialization).length > 0) {
+            options.specialization = specialization;
+        }
+        return options;
+    };
+
+    const setSpecializationValue = (specId: number, value: string) => {
+        const values = { ...specialization };
+        if (value.trim()) {
+            values[specId] = value;
+        } else {
+            delete values[specId];
+        }
+        setSpecialization(values);
+    };
 
+    // Overridden constants are frozen, so they disappear from the reflection
+    // of the specialized module but still need a row to change or clear them
+    const frozenSpecIds = Object.keys(specialization)
+        .map(Number)
+        .filter(
+            specId =>
+                !assembly?.info.spec_constants.some(
+                    constant => constant.spec_id === specId,
+                ),
+        );
+
+    const compile = async () => {
+        const options = compileOptions();
         console.log(options);
         const result = await compileShader(shader, shaderKind, options);
         console.log(result);
 
+        setPassPipeline(null);
+        setPassError('');
+        setDiffView(null);
+        showCompilation(result);
+        lastCompileRequest.current = compileShaderIsSuccess(result)
+            ? { source: shader, shaderKind, options }
+            : null;
+    };
+
+    // Compare the last successful compilation with the current source and
+    // options
+    // Generate host-side structs for the blocks of the last compilation
+    const showHostStructs = async (language: HostLanguage) => {
+        const request = lastCompileRequest.current;
+        if (!request) {
+            return;
+        }
+
+        const result = await generateHostStructs(request, language);
+        setHostStructs(
+            'Success' in result ? result.Success.code : result.Failure.error,
+        );
+    };
+
+    const diffWithLastCompile = async () => {
+        const left = lastCompileRequest.current;
+        if (!left) {
+            return;
+        }
+        const right: CompileRequest = {
+            source: shader,
+            shaderKind,
+            options: compileOptions(),
+        };
+
+        const result = await diffShaders(left, right);
+        console.log(result);
+
+        if (
+            !compileShaderIsSuccess(result.left) ||
+            !compileShaderIsSuccess(result.right)
+        ) {
+            showCompilation(
+                compileShaderIsSuccess(result.left) ? result.right : result.left,
+            );
+            return;
+        }
+
+        const leftInstructions = result.left.Success.assembly.instructions;
+        const rightInstructions = result.right.Success.assembly.instructions;
+        const lines: Array<string> = [];
+        const decorations: Array<[number, string]> = [];
+        for (const line of result.instructions) {
+            if ('Equal' in line) {
+                lines.push(
+                    `  ${rightInstructions[line.Equal.right].instruction}`,
+                );
+            } else if ('Modify' in line) {
+                const { left, right } = line.Modify;
+                decorations.push([lines.length, 'diff-removed']);
+                lines.push(`- ${leftInstructions[left].instruction}`);
+                decorations.push([lines.length, 'pass-changed']);
+                lines.push(`~ ${rightInstructions[right].instruction}`);
+            } else if ('Delete' in line) {
+                decorations.push([lines.length, 'diff-removed']);
+                lines.push(
+                    `- ${leftInstructions[line.Delete.left].instruction}`,
+                );
+            } else {
+                decorations.push([lines.length, 'pass-added']);
+                lines.push(
+                    `+ ${rightInstructions[line.Insert.right].instruction}`,
+                );
+            }
+        }
+
+        showCompilation(result.right);
+        setPassPipeline(null);
+        setAssembly(null);
+        setDiffView({ text: lines.join('\n'), decorations });
+    };
+
+    // Compile without optimization, then run the chosen passes one by one
+    const runPassPipeline = async () => {
+        const passes = (passList.current?.value ?? '')
+            .split(/[\s,]+/)
+            .map(pass => pass.replace(/^--/, ''))
+            .filter(pass => pass);
+        const options = {
+            ...compileOptions(),
+            optimization: OptimizationLevel.Zero,
+        };
+
+        const result = await runPasses(shader, shaderKind, options, passes);
+        console.log(result);
+
+        setDiffView(null);
+        showCompilation(result.compilation);
+        if (compileShaderIsSuccess(result.compilation)) {
+            setPassPipeline({
+                initial: result.compilation.Success.assembly,
+                steps: result.steps,
+            });
+            setPassStep(result.steps.length);
+            if (result.steps.length > 0) {
+                setAssembly(result.steps[result.steps.length - 1].assembly);
+            }
+        } else {
+            setPassPipeline(null);
+        }
+        setPassError(
+            result.error ? `${result.error.pass}: ${result.error.error}` : '',
+        );
+    };
+
+    // Show the module as it was after the given number of passes
+    const selectPassStep = (step: number) => {
+        if (!passPipeline) {
+            return;
+        }
+        setPassStep(step);
+        setAssembly(
+            step === 0
+                ? passPipeline.initial
+                : passPipeline.steps[step - 1].assembly,
+        );
+    };
+
+    const showCompilation = (result: CompileShaderResult) => {
         if (compileShaderIsSuccess(result)) {
             setAssembly(result.Success.assembly);
             setWarning(result.Success.warning);
+            setActiveMacros(result.Success.macros);
+            setActiveVersions(
+                `${targetEnv} ${result.Success.target_env_version}, ` +
+                    `SPIR-V ${result.Success.spirv_version}`,
+            );
             setError('');
+            showDiagnostics(
+                result.Success.diagnostics.messages,
+                sourceFileName,
+            );
+        } else if (compileShaderIsAnalysisFailure(result)) {
+            const failure = result.AnalysisFailure;
+            setAssembly(null);
+            setError(
+                failure.disassembly
+                    ? `; ${failure.error}\n\n${failure.disassembly}`
+                    : failure.error,
+            );
+            showDiagnostics(failure.diagnostics.messages, sourceFileName);
+            setWarning(failure.warning);
+            setActiveMacros({});
+            setActiveVersions('');
         } else {
             setAssembly(null);
             setError(result.Failure.error);
+            showDiagnostics(
+                result.Failure.diagnostics.messages,
+                sourceFileName,
+            );
             setWarning('');
+            setActiveMacros({});
+            setActiveVersions('');
+        }
+    };
+
+    // Show an existing module instead of compiling the source
+    const openSpirv = async (event: React.ChangeEvent<HTMLInputElement>) => {
+        const file = event.target.files?.[0];
+        event.target.value = '';
+        if (!file) {
+            return;
+        }
+
+        const bytes = Array.from(new Uint8Array(await file.arrayBuffer()));
+        const result = await disassembleSpirv({ Bytes: bytes }, loadOptions());
+        console.log(result);
+
+        setWarning('');
+        setActiveMacros({});
+        setPassPipeline(null);
+        setPassError('');
+        setDiffView(null);
+        showDiagnostics([], '');
+
+        if (disassemblyIsSuccess(result)) {
+            const assembly = result.Success.assembly;
+            setAssembly(assembly);
+            setError('');
+            setActiveVersions(
+                `${file.name}, SPIR-V ${result.Success.spirv_version}`,
+            );
+
+            // Line annotations refer to the embedded source, if there is one
+            const embeddedSource =
+                assembly.embedded_sources[assembly.source_file ?? ''];
+            if (embeddedSource !== undefined) {
+                setShader(embeddedSource);
+            }
+        } else {
+            const failure = result.Failure;
+            setAssembly(null);
+            setError(
+                failure.disassembly
+                    ? `; ${failure.error}\n\n${failure.disassembly}`
+                    : failure.error,
+            );
+            setActiveVersions('');
+        }
+    };
+
+    // Assemble the hand-edited disassembly. On errors the edited text is kept
+    // and the errors are underlined
+    const assemble = async () => {
+        const editor = editorDisassemblyRef.current;
+        const model = editor?.getModel();
+        if (!editor || !model) {
+            return;
+        }
+
+        const result = await assembleSpirv(editor.getValue(), loadOptions());
+        console.log(result);
+
+        if (disassemblyIsSuccess(result)) {
+            monaco.editor.setModelMarkers(model, 'spirv-as', []);
+            setPassPipeline(null);
+            setPassError('');
+            setDiffView(null);
+            setAssembly(result.Success.assembly);
+            setError('');
+            setActiveVersions(
+                `Assembled, SPIR-V ${result.Success.spirv_version}`,
+            );
+        } else {
+            monaco.editor.setModelMarkers(
+                model,
+                'spirv-as',
+                diagnosticMarkers(model, result.Failure.diagnostics.messages),
+            );
         }
     };
 
@@ -269,10 +725,27 @@ function App() {
             >
                 <div className='center-element'>
                     <select
-                        value={targetEnv}
+                        value={sourceLanguage}
                         onChange={v =>
-                            setTargetEnv(v.target.value as TargetEnv)
+                            setSourceLanguage(v.target.value as SourceLanguage)
                         }
+                    >
+                        {Array.from(Object.keys(SourceLanguage)).map(
+                            language => {
+                                return (
+                                    <option value={language} key={language}>
+                                        {language}
+                                    </option>
+                                );
+                            },
+                        )}
+                    </select>
+                    <select
+                        value={targetEnv}
+                        onChange={v => {
+                            setTargetEnv(v.target.value as TargetEnv);
+                            setTargetEnvVersion('');
+                        }}
                     >
                         {Array.from(Object.keys(TargetEnv)).map(target => {
                             return (
@@ -282,6 +755,46 @@ function App() {
                             );
                         })}
                     </select>
+                    <select
+                        value={targetEnvVersion}
+                        onChange={v => setTargetEnvVersion(v.target.value)}
+                    >
+                        <option value=''>Default version</option>
+                        {TargetEnvVersions[targetEnv].map(version => (
+                            <option value={version} key={version}>
+                                {targetEnv} {version}
+                            </option>
+                        ))}
+                    </select>
+                    <select
+                        value={spirvVersion}
+                        onChange={v => setSpirvVersion(v.target.value)}
+                    >
+                        <option value=''>Default SPIR-V</option>
+                        {SpirvVersions.map(version => (
+                            <option value={version} key={version}>
+                                SPIR-V {version}
+                            </option>
+                        ))}
+                    </select>
+                    <select
+                        value={optimization}
+                        onChange={v =>
+                            setOptimization(
+                                v.target.value as OptimizationLevel,
+                            )
+                        }
+                    >
+                        {Array.from(Object.keys(OptimizationLevel)).map(
+                            level => {
+                                return (
+                                    <option value={level} key={level}>
+                                        {level}
+                                    </option>
+                                );
+                            },
+                        )}
+                    </select>
                     <select
                         value={shaderKind}
                         onChange={v =>
@@ -310,7 +823,14 @@ function App() {
                             type='text'
                             placeholder='Entry point'
                             ref={entryPoint}
-                            disabled={targetEnv !== TargetEnv.HLSL}
+                            disabled={sourceLanguage !== SourceLanguage.HLSL}
+                        />
+                    </span>
+                    <span>
+                        <input
+                            type='text'
+                            placeholder='Macros (NAME=VALUE ...)'
+                            ref={macroDefinitions}
                         />
                     </span>
                     <span>
@@ -330,6 +850,63 @@ function App() {
                         <label htmlFor='rainbow'>Rainbow colors</label>
                     </span>
                     <button onClick={compile}>Compile</button>
+                    <label title='Disassemble an existing SPIR-V module'>
+                        Open SPIR-V{' '}
+                        <input type='file' accept='.spv' onChange={openSpirv} />
+                    </label>
+                    <span>
+                        <input
+                            type='checkbox'
+                            id='editAssembly'
+                            checked={editingAssembly}
+                            onChange={e => setEditingAssembly(e.target.checked)}
+                        />
+                        <label htmlFor='editAssembly'>Edit disassembly</label>
+                    </span>
+                    {editingAssembly ? (
+                        <button onClick={assemble}>Assemble</button>
+                    ) : undefined}
+                    <span>
+                        <input
+                            type='text'
+                            placeholder='Passes (ssa-rewrite ccp ...)'
+                            title={OptimizerPasses.join(' ')}
+                            ref={passList}
+                        />
+                    </span>
+                    <button onClick={runPassPipeline}>Run passes</button>
+                    <button
+                        onClick={diffWithLastCompile}
+                        title='Compare the last compilation with the current source and options'
+                    >
+                        Diff
+                    </button>
+                    {passPipeline ? (
+                        <select
+                            value={passStep}
+                            onChange={v =>
+                                selectPassStep(Number(v.target.value))
+                            }
+                        >
+                            <option value={0}>Before passes</option>
+                            {passPipeline.steps.map((step, i) => (
+                                <option value={i + 1} key={i}>
+                                    {`${i + 1}. ${step.pass} ` +
+                                        `(+${step.added.length} ` +
+                                        `-${step.removed.length} ` +
+                                        `~${step.changed.length})`}
+                                </option>
+                            ))}
+                        </select>
+                    ) : undefined}
+                    <span>{activeVersions}</span>
+                    <span title='Macros in effect for the shown disassembly'>
+                        {Object.entries(activeMacros)
+                            .map(([name, value]) =>
+                                value === null ? name : `${name}=${value}`,
+                            )
+                            .join(' ')}
+                    </span>
                 </div>
             </div>
 
@@ -354,11 +931,13 @@ function App() {
                             </td>
                             <td>
                                 <Editor
-                                    value={error || assemblyText}
+                                    value={
+                                        error || diffView?.text || assemblyText
+                                    }
                                     height={`calc(100vh - ${TOP_BAR_HEIGHT})`}
                                     width='50vw'
                                     theme={'vs-dark'}
-                                    options={{ readOnly: true }}
+                                    options={{ readOnly: !editingAssembly }}
                                     onMount={handleEditorDisassemblyDidMount}
                                 ></Editor>
                             </td>
@@ -367,10 +946,232 @@ function App() {
                 </table>
             </span>
 
-            {warning ? (
+            {assembly && assembly.info.resources.length > 0 ? (
+                <details>
+                    <summary>Resources</summary>
+                    <table style={{ margin: '0 auto', textAlign: 'left' }}>
+                        <thead>
+                            <tr>
+                                <th>Set</th>
+                                <th>Binding</th>
+                                <th>Descriptor</th>
+                                <th>Storage class</th>
+                                <th>Type</th>
+                                <th>Name</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {assembly.info.resources.map(resource => (
+                                <tr key={resource.variable}>
+                                    <td>{resource.set ?? '-'}</td>
+                                    <td>{resource.binding ?? '-'}</td>
+                                    <td>{resource.descriptor_type ?? '-'}</td>
+                                    <td>{resource.storage_class}</td>
+                                    <td>
+                                        {resource.type_name}
+                                        {formatArraySize(resource.array_size)}
+                                    </td>
+                                    <td>{resource.name ?? ''}</td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                </details>
+            ) : undefined}
+
+            {assembly?.info.spec_constants.some(
+                constant => constant.spec_id !== null,
+            ) || frozenSpecIds.length > 0 ? (
+                <details>
+                    <summary>Specialization constants</summary>
+                    <table style={{ margin: '0 auto', textAlign: 'left' }}>
+                        <thead>
+                            <tr>
+                                <th>SpecId</th>
+                                <th>Name</th>
+                                <th>Type</th>
+                                <th>Default</th>
+                                <th>Value</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {assembly?.info.spec_constants.map(constant =>
+                                constant.spec_id !== null ? (
+                                    <tr key={constant.id}>
+                                        <td>{constant.spec_id}</td>
+                                        <td>{constant.name ?? ''}</td>
+                                        <td>{constant.type_name}</td>
+                                        <td>{constant.default}</td>
+                                        <td>
+                                            <input
+                                                type='text'
+                                                value={
+                                                    specialization[
+                                                        constant.spec_id
+                                                    ] ?? ''
+                                                }
+                                                placeholder={constant.default}
+                                                onChange={e =>
+                                                    setSpecializationValue(
+                                                        constant.spec_id!,
+                                                        e.target.value,
+                                                    )
+                                                }
+                                            />
+                                        </td>
+                                    </tr>
+                                ) : undefined,
+                            )}
+                            {frozenSpecIds.map(specId => (
+                                <tr key={`frozen-${specId}`}>
+                                    <td>{specId}</td>
+                                    <td colSpan={3}>frozen</td>
+                                    <td>
+                                        <input
+                                            type='text'
+                                            value={specialization[specId]}
+                                            onChange={e =>
+                                                setSpecializationValue(
+                                                    specId,
+                                                    e.target.value,
+                                                )
+                                            }
+                                        />
+                                    </td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                </details>
+            ) : undefined}
+
+            {assembly?.info.entry_points.map(entryPoint => (
+                <details key={entryPoint.function}>
+                    <summary>
+                        {entryPoint.execution_model} entry point{' '}
+                        {entryPoint.name}
+                    </summary>
+                    <table style={{ margin: '0 auto', textAlign: 'left' }}>
+                        <thead>
+                            <tr>
+                                <th>Storage class</th>
+                                <th>Location</th>
+                                <th>Component</th>
+                                <th>Built-in</th>
+                                <th>Qualifiers</th>
+                                <th>Type</th>
+                                <th>Name</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {entryPoint.interface.flatMap(variable => [
+                                variable,
+                                ...variable.members.map(member => ({
+                                    ...member,
+                                    storage_class: variable.storage_class,
+                                    name: `${variable.name ?? ''}.${
+                                        member.name ?? member.index
+                                    }`,
+                                })),
+                            ]).map((variable, i) => (
+                                <tr key={i}>
+                                    <td>{variable.storage_class}</td>
+                                    <td>{variable.location ?? ''}</td>
+                                    <td>{variable.component ?? ''}</td>
+                                    <td>{variable.built_in ?? ''}</td>
+                                    <td>{variable.qualifiers.join(' ')}</td>
+                                    <td>{variable.type_name}</td>
+                                    <td>{variable.name ?? ''}</td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                </details>
+            ))}
+
+            {assembly?.info.structs
+                .filter(layout =>
+                    layout.members.every(member => member.offset !== null),
+                )
+                .map(layout => (
+                    <details key={layout.id}>
+                        <summary>
+                            struct {layout.name}
+                            {layout.size !== null
+                                ? `, ${layout.size} bytes`
+                                : ''}
+                            {layout.rules.length > 0
+                                ? ` (${layout.rules.join(', ')})`
+                                : ''}
+                        </summary>
+                        <table
+                            style={{ margin: '0 auto', textAlign: 'left' }}
+                        >
+                            <thead>
+                                <tr>
+                                    <th>Offset</th>
+                                    <th>Size</th>
+                                    <th>Type</th>
+                                    <th>Name</th>
+                                    <th>Array stride</th>
+                                    <th>Matrix stride</th>
+                                    <th>Matrix layout</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {layout.members.map(member => (
+                                    <tr key={member.index}>
+                                        <td>{member.offset}</td>
+                                        <td>{member.size ?? 'runtime'}</td>
+                                        <td>{member.type_name}</td>
+                                        <td>{member.name ?? member.index}</td>
+                                        <td>{member.array_stride ?? ''}</td>
+                                        <td>{member.matrix_stride ?? ''}</td>
+                                        <td>{member.matrix_layout ?? ''}</td>
+                                    </tr>
+                                ))}
+                                {layout.padding.map(padding => (
+                                    <tr key={`padding-${padding.offset}`}>
+                                        <td>{padding.offset}</td>
+                                        <td>{padding.size}</td>
+                                        <td colSpan={5}>padding</td>
+                                    </tr>
+                                ))}
+                            </tbody>
+                        </table>
+                        {layout.warnings.map((warning, i) => (
+                            <div key={i}>{warning}</div>
+                        ))}
+                    </details>
+                ))}
+
+            {assembly?.info.structs.some(layout =>
+                layout.members.every(member => member.offset !== null),
+            ) ? (
+                <details>
+                    <summary>Host structs</summary>
+                    <button onClick={() => showHostStructs('Rust')}>
+                        Rust
+                    </button>
+                    <button onClick={() => showHostStructs('Cpp')}>
+                        C++
+                    </button>
+                    {hostStructs ? (
+                        <textarea
+                            readOnly={true}
+                            value={hostStructs}
+                            cols={120}
+                            wrap='off'
+                            style={{ height: '50vh', width: '90%' }}
+                        ></textarea>
+                    ) : undefined}
+                </details>
+            ) : undefined}
+
+            {warningText ? (
                 <textarea
                     readOnly={true}
-                    value={warning}
+                    value={warningText}
                     cols={120}
                     wrap='off'
                     style={{ height: '50vh', width: '90%' }}
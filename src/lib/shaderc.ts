@@ -2,12 +2,120 @@ import { invoke } from '@tauri-apps/api/tauri';
 
 export interface AnnotatedDisassembly {
     header: string | null;
+    source_file: string | null;
+    embedded_sources: { [file: string]: string };
     instructions: Array<AnnotatedInstruction>;
+    validation_errors: Array<string>;
+    validation_skipped: string | null;
+    info: ModuleInfo;
+}
+
+export interface ModuleInfo {
+    resources: Array<Resource>;
+    entry_points: Array<EntryPoint>;
+    structs: Array<StructLayout>;
+    spec_constants: Array<SpecConstant>;
+}
+
+export interface SpecConstant {
+    id: number;
+    name: string | null;
+    spec_id: number | null;
+    type_name: string;
+    default: string;
+}
+
+export type LayoutRule = 'Std140' | 'Std430' | 'Scalar';
+
+export interface MemberLayout {
+    index: number;
+    name: string | null;
+    type_name: string;
+    offset: number | null;
+    size: number | null;
+    array_stride: number | null;
+    matrix_stride: number | null;
+    matrix_layout: 'ColumnMajor' | 'RowMajor' | null;
+}
+
+export interface StructLayout {
+    id: number;
+    name: string;
+    members: Array<MemberLayout>;
+    size: number | null;
+    padding: Array<{ offset: number; size: number }>;
+    rules: Array<LayoutRule>;
+    warnings: Array<string>;
+}
+
+export interface EntryPoint {
+    name: string;
+    execution_model: string;
+    function: number;
+    interface: Array<InterfaceVariable>;
+}
+
+export interface InterfaceMember {
+    index: number;
+    name: string | null;
+    type_name: string;
+    location: number | null;
+    component: number | null;
+    built_in: string | null;
+    qualifiers: Array<string>;
+}
+
+export interface InterfaceVariable extends Omit<InterfaceMember, 'index'> {
+    variable: number;
+    storage_class: 'Input' | 'Output';
+    members: Array<InterfaceMember>;
+}
+
+export type DescriptorType =
+    | 'UniformBuffer'
+    | 'StorageBuffer'
+    | 'Sampler'
+    | 'SampledImage'
+    | 'CombinedImageSampler'
+    | 'StorageImage'
+    | 'UniformTexelBuffer'
+    | 'StorageTexelBuffer'
+    | 'InputAttachment'
+    | 'AccelerationStructure';
+
+export type ArraySize =
+    | { Constant: number }
+    | { SpecConstant: string }
+    | 'Runtime';
+
+export interface Resource {
+    variable: number;
+    name: string | null;
+    set: number | null;
+    binding: number | null;
+    storage_class: string;
+    descriptor_type: DescriptorType | null;
+    array_size: ArraySize | null;
+    type_name: string;
+}
+
+export function formatArraySize(size: ArraySize | null): string {
+    if (size === null) {
+        return '';
+    } else if (size === 'Runtime') {
+        return '[]';
+    } else if ('Constant' in size) {
+        return `[${size.Constant}]`;
+    } else {
+        return `[${size.SpecConstant}]`;
+    }
 }
 
 export interface AnnotatedInstruction {
+    index: number;
     line: LineAnnotation | null;
     instruction: string;
+    validation_errors: Array<string>;
 }
 
 export interface LineAnnotation {
@@ -15,17 +123,55 @@ export interface LineAnnotation {
     line: number;
 }
 
+export enum Severity {
+    Error = 'Error',
+    Warning = 'Warning',
+}
+
+export interface Diagnostic {
+    severity: Severity;
+    file: string | null;
+    line: number | null;
+    column: number | null;
+    message: string;
+}
+
+export interface Diagnostics {
+    messages: Array<Diagnostic>;
+    error_count: number;
+    warning_count: number;
+}
+
+export type MacroDefinitions = { [name: string]: string | null };
+
 export interface CompileShaderSuccessData {
     assembly: AnnotatedDisassembly;
     warning: string;
+    diagnostics: Diagnostics;
+    macros: MacroDefinitions;
+    target_env_version: string;
+    spirv_version: string;
 }
 export type CompileShaderSuccess = { Success: CompileShaderSuccessData };
 export interface CompileShaderFailureData {
     error: string;
+    diagnostics: Diagnostics;
 }
 export type CompileShaderFailure = { Failure: CompileShaderFailureData };
+export interface CompileShaderAnalysisFailureData {
+    error: string;
+    disassembly: string | null;
+    warning: string;
+    diagnostics: Diagnostics;
+}
+export type CompileShaderAnalysisFailure = {
+    AnalysisFailure: CompileShaderAnalysisFailureData;
+};
 
-export type CompileShaderResult = CompileShaderSuccess | CompileShaderFailure;
+export type CompileShaderResult =
+    | CompileShaderSuccess
+    | CompileShaderFailure
+    | CompileShaderAnalysisFailure;
 
 export function compileShaderIsSuccess(
     result: CompileShaderResult,
@@ -33,6 +179,12 @@ export function compileShaderIsSuccess(
     return result.hasOwnProperty('Success');
 }
 
+export function compileShaderIsAnalysisFailure(
+    result: CompileShaderResult,
+): result is CompileShaderAnalysisFailure {
+    return result.hasOwnProperty('AnalysisFailure');
+}
+
 export enum ShaderKindRaster {
     Vertex = 'Vertex',
     Fragment = 'Fragment',
@@ -62,17 +214,115 @@ export type ShaderKind =
     | ShaderKindCompute
     | ShaderKindMesh;
 
+export enum SourceLanguage {
+    GLSL = 'GLSL',
+    HLSL = 'HLSL',
+}
+
 export enum TargetEnv {
     Vulkan = 'Vulkan',
     OpenGL = 'OpenGL',
-    HLSL = 'HLSL',
 }
 
+export enum OptimizationLevel {
+    Zero = 'Zero',
+    Size = 'Size',
+    Performance = 'Performance',
+}
+
+export const TargetEnvVersions: { [env in TargetEnv]: Array<string> } = {
+    [TargetEnv.Vulkan]: ['1.0', '1.1', '1.2', '1.3'],
+    [TargetEnv.OpenGL]: ['4.5'],
+};
+
+export const SpirvVersions = ['1.0', '1.1', '1.2', '1.3', '1.4', '1.5', '1.6'];
+
+export type HlslRegisterClass = 'b' | 't' | 's' | 'u';
+
+export interface HlslRegisterBinding {
+    register: string;
+    set: number;
+    binding: number;
+}
+
+/**
+ * Native 16-bit types are not available yet, as the shaderc crate does not
+ * forward libshaderc's `-enable-16bit-types` option.
+ */
+export interface HlslOptions {
+    registerShifts?: { [register in HlslRegisterClass]?: number };
+    registerBindings?: Array<HlslRegisterBinding>;
+    functionality1?: boolean;
+    offsets?: boolean;
+    ioMapping?: boolean;
+}
+
+export type ResourceKind =
+    | 'Image'
+    | 'Sampler'
+    | 'Texture'
+    | 'Buffer'
+    | 'StorageBuffer'
+    | 'UnorderedAccessView';
+
 export interface CompileShaderOptions {
+    sourceLanguage?: SourceLanguage;
     targetEnv?: TargetEnv;
+    targetEnvVersion?: string;
+    spirvVersion?: string;
     fileName?: string;
     limitResultNameLength?: number;
     entryPoint?: string;
+    optimization?: OptimizationLevel;
+    macros?: MacroDefinitions;
+    sources?: { [path: string]: string };
+    includeDirectories?: Array<string>;
+    maxIncludeDepth?: number;
+    forcedVersionProfile?: string;
+    autoMapLocations?: boolean;
+    autoBindUniforms?: boolean;
+    autoCombinedImageSampler?: boolean;
+    bindingBase?: { [kind in ResourceKind]?: number };
+    invertY?: boolean;
+    nanClamp?: boolean;
+    hlsl?: HlslOptions;
+    specialization?: Specialization;
+}
+
+/**
+ * Values for specialization constants by SpecId, written like literals in the
+ * assembly.
+ */
+export type Specialization = { [specId: number]: string };
+
+/**
+ * Whether a line annotation refers to the compiled source itself rather than
+ * to a file pulled in by `#include`.
+ */
+export function isSourceFileLine(
+    assembly: AnnotatedDisassembly,
+    line: LineAnnotation,
+): boolean {
+    return assembly.source_file === null || line.file === assembly.source_file;
+}
+
+/**
+ * Parses a space separated list of `NAME` or `NAME=VALUE` definitions,
+ * the same way they would be passed to the compiler as `-D` flags.
+ */
+export function parseMacroDefinitions(text: string): MacroDefinitions {
+    const macros: MacroDefinitions = {};
+    for (const definition of text.split(/\s+/).filter(d => d)) {
+        const separator = definition.indexOf('=');
+        if (separator < 0) {
+            macros[definition] = null;
+        } else {
+            macros[definition.slice(0, separator)] = definition.slice(
+                separator + 1,
+            );
+        }
+    }
+    return macros;
 }
 
 export async function compileShader(
@@ -80,9 +330,261 @@ export async function compileShader(
     shaderKind: ShaderKind,
     options: CompileShaderOptions = {},
 ): Promise<CompileShaderResult> {
-    return await invoke('compile_shader', {
-        source,
-        shaderKind,
-        options,
-    });
+    try {
+        return await invoke('compile_shader', {
+            source,
+            shaderKind,
+            options,
+        });
+    } catch (e) {
+        // The backend rejects options it cannot deserialize
+        return compileFailure(String(e));
+    }
+}
+
+function compileFailure(error: string): CompileShaderFailure {
+    return {
+        Failure: {
+            error,
+            diagnostics: {
+                messages: [
+                    {
+                        severity: Severity.Error,
+                        file: null,
+                        line: null,
+                        column: null,
+                        message: error,
+                    },
+                ],
+                error_count: 1,
+                warning_count: 0,
+            },
+        },
+    };
+}
+
+/**
+ * spirv-opt passes that can be run one at a time, named after their flags.
+ */
+export const OptimizerPasses = [
+    'inline-entry-points-exhaustive',
+    'inline-entry-points-opaque',
+    'merge-return',
+    'eliminate-local-single-block',
+    'eliminate-local-single-store',
+    'eliminate-local-multi-store',
+    'ssa-rewrite',
+    'convert-local-access-chains',
+    'combine-access-chains',
+    'private-to-local',
+    'copy-propagate-arrays',
+    'ccp',
+    'simplify-instructions',
+    'strength-reduction',
+    'redundancy-elimination',
+    'local-redundancy-elimination',
+    'eliminate-insert-extract',
+    'eliminate-dead-inserts',
+    'vector-dce',
+    'reduce-load-size',
+    'if-conversion',
+    'eliminate-dead-branches',
+    'merge-blocks',
+    'cfg-cleanup',
+    'loop-peeling',
+    'loop-invariant-code-motion',
+    'eliminate-dead-code-aggressive',
+    'eliminate-dead-functions',
+    'eliminate-dead-variables',
+    'eliminate-dead-const',
+    'fold-spec-const-op-composite',
+    'freeze-spec-const',
+    'unify-const',
+    'remove-duplicates',
+    'compact-ids',
+    'strip-debug',
+];
+
+export interface PassStep {
+    pass: string;
+    assembly: AnnotatedDisassembly;
+    // Positions in the instructions of this step
+    added: Array<number>;
+    changed: Array<number>;
+    // Positions in the instructions of the previous step
+    removed: Array<number>;
+    messages: Array<string>;
+}
+
+export interface PassError {
+    pass: string;
+    error: string;
+}
+
+export interface PassPipelineResult {
+    compilation: CompileShaderResult;
+    steps: Array<PassStep>;
+    error: PassError | null;
+}
+
+/**
+ * Compiles a shader and runs the given spirv-opt passes on it one at a time,
+ * returning a snapshot after every pass.
+ */
+export async function runPasses(
+    source: string,
+    shaderKind: ShaderKind,
+    options: CompileShaderOptions,
+    passes: Array<string>,
+): Promise<PassPipelineResult> {
+    try {
+        return await invoke('run_passes', {
+            source,
+            shaderKind,
+            options,
+            passes,
+        });
+    } catch (e) {
+        // Unknown pass names are rejected when deserializing
+        return {
+            compilation: compileFailure(String(e)),
+            steps: [],
+            error: null,
+        };
+    }
+}
+
+export interface CompileRequest {
+    source: string;
+    shaderKind: ShaderKind;
+    options: CompileShaderOptions;
+}
+
+// Positions refer to the instructions of the left and right assembly
+export type InstructionDiff =
+    | { Equal: { left: number; right: number } }
+    | { Modify: { left: number; right: number } }
+    | { Delete: { left: number } }
+    | { Insert: { right: number } };
+
+export interface ShaderDiffResult {
+    left: CompileShaderResult;
+    right: CompileShaderResult;
+    instructions: Array<InstructionDiff>;
+}
+
+/**
+ * Compiles two shaders and aligns their instructions, ignoring differences in
+ * id numbering.
+ */
+export async function diffShaders(
+    left: CompileRequest,
+    right: CompileRequest,
+): Promise<ShaderDiffResult> {
+    try {
+        return await invoke('diff_shaders', { left, right });
+    } catch (e) {
+        const failure = compileFailure(String(e));
+        return { left: failure, right: failure, instructions: [] };
+    }
+}
+
+export type HostLanguage = 'Rust' | 'Cpp';
+
+export type HostStructsResult =
+    | { Success: { code: string } }
+    | { Failure: { error: string } };
+
+/**
+ * Compiles a shader and generates Rust or C++ structs that match the layout of
+ * its uniform, storage and push constant blocks.
+ */
+export async function generateHostStructs(
+    request: CompileRequest,
+    language: HostLanguage,
+): Promise<HostStructsResult> {
+    try {
+        return await invoke('generate_host_structs', { request, language });
+    } catch (e) {
+        return { Failure: { error: String(e) } };
+    }
+}
+
+export interface LoadOptions {
+    limitResultNameLength?: number;
+    targetEnv?: TargetEnv;
+    targetEnvVersion?: string;
+}
+
+export type SpirvInput = { Bytes: Array<number> } | { Path: string };
+
+export interface DisassemblySuccessData {
+    assembly: AnnotatedDisassembly;
+    spirv_version: string;
+}
+export type DisassemblySuccess = { Success: DisassemblySuccessData };
+export interface DisassemblyFailureData {
+    error: string;
+    disassembly: string | null;
+    diagnostics: Diagnostics;
+}
+export type DisassemblyFailure = { Failure: DisassemblyFailureData };
+
+export type DisassemblyResult = DisassemblySuccess | DisassemblyFailure;
+
+export function disassemblyIsSuccess(
+    result: DisassemblyResult,
+): result is DisassemblySuccess {
+    return result.hasOwnProperty('Success');
+}
+
+/**
+ * Annotates an existing SPIR-V module, e.g. one produced by another toolchain.
+ */
+export async function disassembleSpirv(
+    input: SpirvInput,
+    options: LoadOptions = {},
+): Promise<DisassemblyResult> {
+    try {
+        return await invoke('disassemble_spirv', { input, options });
+    } catch (e) {
+        return disassemblyFailure(String(e));
+    }
+}
+
+/**
+ * Assembles edited SPIR-V text, in the syntax of the annotated disassembly or
+ * in standard spirv-as syntax. Parse errors are reported with line numbers.
+ */
+export async function assembleSpirv(
+    text: string,
+    options: LoadOptions = {},
+): Promise<DisassemblyResult> {
+    try {
+        return await invoke('assemble_spirv', { text, options });
+    } catch (e) {
+        return disassemblyFailure(String(e));
+    }
+}
+
+function disassemblyFailure(error: string): DisassemblyFailure {
+    return {
+        Failure: {
+            error,
+            disassembly: null,
+            diagnostics: {
+                messages: [
+                    {
+                        severity: Severity.Error,
+                        file: null,
+                        line: null,
+                        column: null,
+                        message: error,
+                    },
+                ],
+                error_count: 1,
+                warning_count: 0,
+            },
+        },
+    };
 }